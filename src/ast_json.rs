@@ -0,0 +1,370 @@
+use crate::{
+    interpreter::{Expr, FunctionDecl, Stmt, Value},
+    token::Token,
+};
+
+/// Serializa el programa como un arreglo JSON de nodos `{"type": ..., ...}`.
+pub fn program_to_json(statements: &[Stmt]) -> String {
+    array(statements.iter().map(stmt_to_json))
+}
+
+pub fn stmt_to_json(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Break(keyword) => object(&[("type", string("break")), ("line", line(keyword))]),
+        Stmt::Continue(keyword) => object(&[("type", string("continue")), ("line", line(keyword))]),
+        Stmt::Import { keyword, path } => object(&[
+            ("type", string("import")),
+            (
+                "path",
+                optional(
+                    path.literal
+                        .clone()
+                        .map(|literal| value_to_json(&literal.into())),
+                ),
+            ),
+            ("line", line(keyword)),
+        ]),
+        Stmt::Expression(expr) => object(&[
+            ("type", string("expression")),
+            ("expression", expr_to_json(expr)),
+        ]),
+        Stmt::Function(function) => function_to_json(function),
+        Stmt::Print { expr, newline } => object(&[
+            ("type", string("print")),
+            ("newline", newline.to_string()),
+            ("expression", expr_to_json(expr)),
+        ]),
+        Stmt::Return { keyword, value } => object(&[
+            ("type", string("return")),
+            ("line", line(keyword)),
+            ("value", optional(value.as_ref().map(expr_to_json))),
+        ]),
+        Stmt::Block(statements) => object(&[
+            ("type", string("block")),
+            ("statements", program_to_json(statements)),
+        ]),
+        Stmt::Class {
+            name,
+            superclass,
+            methods,
+            class_methods,
+        } => object(&[
+            ("type", string("class")),
+            ("name", string(&name.lexeme)),
+            ("line", line(name)),
+            (
+                "superclass",
+                optional(superclass.as_ref().map(expr_to_json)),
+            ),
+            (
+                "methods",
+                array(methods.iter().map(|method| function_to_json(method))),
+            ),
+            (
+                "class_methods",
+                array(class_methods.iter().map(|method| function_to_json(method))),
+            ),
+        ]),
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => object(&[
+            ("type", string("if")),
+            ("condition", expr_to_json(condition)),
+            ("then", stmt_to_json(then_branch)),
+            (
+                "else",
+                optional(else_branch.as_ref().map(|stmt| stmt_to_json(stmt))),
+            ),
+        ]),
+        Stmt::Switch {
+            subject,
+            cases,
+            default,
+        } => object(&[
+            ("type", string("switch")),
+            ("subject", expr_to_json(subject)),
+            (
+                "cases",
+                array(cases.iter().map(|(value, body)| {
+                    object(&[("value", expr_to_json(value)), ("body", stmt_to_json(body))])
+                })),
+            ),
+            (
+                "default",
+                optional(default.as_ref().map(|stmt| stmt_to_json(stmt))),
+            ),
+        ]),
+        Stmt::Var { name, initializer } => object(&[
+            ("type", string("var")),
+            ("name", string(&name.lexeme)),
+            ("line", line(name)),
+            (
+                "initializer",
+                optional(initializer.as_ref().map(expr_to_json)),
+            ),
+        ]),
+        Stmt::DoWhile { body, condition } => object(&[
+            ("type", string("do_while")),
+            ("body", stmt_to_json(body)),
+            ("condition", expr_to_json(condition)),
+        ]),
+        Stmt::While {
+            condition,
+            body,
+            increment,
+        } => object(&[
+            ("type", string("while")),
+            ("condition", expr_to_json(condition)),
+            ("body", stmt_to_json(body)),
+            ("increment", optional(increment.as_ref().map(expr_to_json))),
+        ]),
+    }
+}
+
+pub fn expr_to_json(expr: &Expr) -> String {
+    match expr {
+        Expr::Assign { name, value, .. } => object(&[
+            ("type", string("assign")),
+            ("name", string(&name.lexeme)),
+            ("line", line(name)),
+            ("value", expr_to_json(value)),
+        ]),
+        Expr::Binary {
+            left,
+            operator,
+            right,
+        } => object(&[
+            ("type", string("binary")),
+            ("op", string(&operator.lexeme)),
+            ("line", line(operator)),
+            ("left", expr_to_json(left)),
+            ("right", expr_to_json(right)),
+        ]),
+        Expr::Call {
+            callee,
+            paren,
+            arguments,
+        } => object(&[
+            ("type", string("call")),
+            ("line", line(paren)),
+            ("callee", expr_to_json(callee)),
+            ("arguments", array(arguments.iter().map(expr_to_json))),
+        ]),
+        Expr::Get {
+            object: target,
+            name,
+        } => object(&[
+            ("type", string("get")),
+            ("name", string(&name.lexeme)),
+            ("line", line(name)),
+            ("object", expr_to_json(target)),
+        ]),
+        Expr::Grouping(inner) => object(&[
+            ("type", string("grouping")),
+            ("expression", expr_to_json(inner)),
+        ]),
+        Expr::Index {
+            object: target,
+            bracket,
+            index,
+        } => object(&[
+            ("type", string("index")),
+            ("line", line(bracket)),
+            ("object", expr_to_json(target)),
+            ("index", expr_to_json(index)),
+        ]),
+        Expr::Slice {
+            object: target,
+            bracket,
+            start,
+            end,
+        } => object(&[
+            ("type", string("slice")),
+            ("line", line(bracket)),
+            ("object", expr_to_json(target)),
+            ("start", expr_to_json(start)),
+            ("end", expr_to_json(end)),
+        ]),
+        Expr::IndexSet {
+            object: target,
+            bracket,
+            index,
+            operator,
+            value,
+        } => object(&[
+            ("type", string("index_set")),
+            ("line", line(bracket)),
+            ("object", expr_to_json(target)),
+            ("index", expr_to_json(index)),
+            (
+                "operator",
+                optional(operator.as_ref().map(|op| string(&op.lexeme))),
+            ),
+            ("value", expr_to_json(value)),
+        ]),
+        Expr::Lambda(function) => object(&[
+            ("type", string("lambda")),
+            ("line", line(&function.name)),
+            (
+                "params",
+                array(function.params.iter().map(|param| string(&param.lexeme))),
+            ),
+            ("variadic", function.variadic.to_string()),
+            ("getter", function.getter.to_string()),
+            ("body", program_to_json(&function.body)),
+        ]),
+        Expr::List(elements) => object(&[
+            ("type", string("list")),
+            ("elements", array(elements.iter().map(expr_to_json))),
+        ]),
+        Expr::Literal(value) => {
+            object(&[("type", string("literal")), ("value", value_to_json(value))])
+        }
+        Expr::Logical {
+            left,
+            operator,
+            right,
+        } => object(&[
+            ("type", string("logical")),
+            ("op", string(&operator.lexeme)),
+            ("line", line(operator)),
+            ("left", expr_to_json(left)),
+            ("right", expr_to_json(right)),
+        ]),
+        Expr::Map { brace, entries } => object(&[
+            ("type", string("map")),
+            ("line", line(brace)),
+            (
+                "entries",
+                array(entries.iter().map(|(key, value)| {
+                    object(&[("key", expr_to_json(key)), ("value", expr_to_json(value))])
+                })),
+            ),
+        ]),
+        Expr::Postfix { operand, operator } => object(&[
+            ("type", string("postfix")),
+            ("op", string(&operator.lexeme)),
+            ("line", line(operator)),
+            ("operand", expr_to_json(operand)),
+        ]),
+        Expr::Prefix { operator, operand } => object(&[
+            ("type", string("prefix")),
+            ("op", string(&operator.lexeme)),
+            ("line", line(operator)),
+            ("operand", expr_to_json(operand)),
+        ]),
+        Expr::Set {
+            object: target,
+            name,
+            operator,
+            value,
+        } => object(&[
+            ("type", string("set")),
+            ("name", string(&name.lexeme)),
+            ("line", line(name)),
+            ("object", expr_to_json(target)),
+            (
+                "operator",
+                optional(operator.as_ref().map(|op| string(&op.lexeme))),
+            ),
+            ("value", expr_to_json(value)),
+        ]),
+        Expr::Super {
+            keyword, method, ..
+        } => object(&[
+            ("type", string("super")),
+            ("method", string(&method.lexeme)),
+            ("line", line(keyword)),
+        ]),
+        Expr::Ternary {
+            condition,
+            then_branch,
+            else_branch,
+        } => object(&[
+            ("type", string("ternary")),
+            ("condition", expr_to_json(condition)),
+            ("then", expr_to_json(then_branch)),
+            ("else", expr_to_json(else_branch)),
+        ]),
+        Expr::This { keyword, .. } => object(&[("type", string("this")), ("line", line(keyword))]),
+        Expr::Unary { operator, right } => object(&[
+            ("type", string("unary")),
+            ("op", string(&operator.lexeme)),
+            ("line", line(operator)),
+            ("right", expr_to_json(right)),
+        ]),
+        Expr::Variable { name, .. } => object(&[
+            ("type", string("variable")),
+            ("name", string(&name.lexeme)),
+            ("line", line(name)),
+        ]),
+    }
+}
+
+fn function_to_json(function: &FunctionDecl) -> String {
+    object(&[
+        ("type", string("function")),
+        ("name", string(&function.name.lexeme)),
+        ("line", line(&function.name)),
+        (
+            "params",
+            array(function.params.iter().map(|param| string(&param.lexeme))),
+        ),
+        ("variadic", function.variadic.to_string()),
+        ("getter", function.getter.to_string()),
+        ("body", program_to_json(&function.body)),
+    ])
+}
+
+fn value_to_json(value: &Value) -> String {
+    match value {
+        // JSON no admite infinitos ni NaN.
+        Value::Number(n) if !n.is_finite() => "null".to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Str(s) => string(s),
+        Value::Bool(b) => b.to_string(),
+        Value::Nil => "null".to_string(),
+        // El parser solo produce literales de los tipos anteriores.
+        value => string(&value.to_string()),
+    }
+}
+
+fn line(token: &Token) -> String {
+    token.line.to_string()
+}
+
+fn optional(value: Option<String>) -> String {
+    value.unwrap_or_else(|| "null".to_string())
+}
+
+fn object(fields: &[(&str, String)]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("{}:{value}", string(key)))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn array(items: impl Iterator<Item = String>) -> String {
+    format!("[{}]", items.collect::<Vec<_>>().join(","))
+}
+
+fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,229 @@
+use crate::{
+    interpreter::{Expr, FunctionDecl, Stmt},
+    token::{Token, TokenType},
+};
+
+/// Representa una expresión en forma parentizada, al estilo Lisp: `(- (* 2 3) 1)`.
+pub fn print_expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Assign { name, value, .. } => parenthesize(&format!("= {}", name.lexeme), &[value]),
+        Expr::Binary {
+            left,
+            operator,
+            right,
+        }
+        | Expr::Logical {
+            left,
+            operator,
+            right,
+        } => parenthesize(&operator.lexeme, &[left, right]),
+        Expr::Call {
+            callee, arguments, ..
+        } => {
+            let mut exprs = vec![callee.as_ref()];
+            exprs.extend(arguments);
+            parenthesize("call", &exprs)
+        }
+        Expr::Get { object, name } => format!("(. {} {})", print_expr(object), name.lexeme),
+        Expr::Grouping(inner) => parenthesize("group", &[inner]),
+        Expr::Index { object, index, .. } => parenthesize("[]", &[object, index]),
+        Expr::Slice {
+            object, start, end, ..
+        } => parenthesize("[..]", &[object, start, end]),
+        Expr::IndexSet {
+            object,
+            index,
+            operator,
+            value,
+            ..
+        } => format!(
+            "({} ([] {} {}) {})",
+            assign_symbol(operator),
+            print_expr(object),
+            print_expr(index),
+            print_expr(value)
+        ),
+        Expr::Lambda(function) => print_function("lambda", function),
+        Expr::List(elements) => {
+            let elements: Vec<&Expr> = elements.iter().collect();
+            parenthesize("list", &elements)
+        }
+        Expr::Literal(value) => value.to_string(),
+        Expr::Map { entries, .. } => {
+            let mut out = "(map".to_string();
+            for (key, value) in entries {
+                out.push_str(&format!(" ({} {})", print_expr(key), print_expr(value)));
+            }
+            out.push(')');
+            out
+        }
+        Expr::Postfix { operand, operator } => {
+            format!("({} {})", print_expr(operand), operator.lexeme)
+        }
+        Expr::Prefix { operator, operand } => parenthesize(&operator.lexeme, &[operand]),
+        Expr::Set {
+            object,
+            name,
+            operator,
+            value,
+        } => format!(
+            "({} (. {} {}) {})",
+            assign_symbol(operator),
+            print_expr(object),
+            name.lexeme,
+            print_expr(value)
+        ),
+        Expr::Super { method, .. } => format!("(super {})", method.lexeme),
+        Expr::Ternary {
+            condition,
+            then_branch,
+            else_branch,
+        } => parenthesize("?:", &[condition, then_branch, else_branch]),
+        Expr::This { .. } => "this".to_string(),
+        Expr::Unary { operator, right } => parenthesize(&operator.lexeme, &[right]),
+        Expr::Variable { name, .. } => name.lexeme.clone(),
+    }
+}
+
+pub fn print_stmt(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Break(_) => "(break)".to_string(),
+        Stmt::Continue(_) => "(continue)".to_string(),
+        Stmt::Expression(expr) => parenthesize(";", &[expr]),
+        Stmt::Function(function) => print_function("fun", function),
+        Stmt::Import { path, .. } => format!("(import {})", path.lexeme),
+        Stmt::Print { expr, newline } => {
+            parenthesize(if *newline { "print" } else { "write" }, &[expr])
+        }
+        Stmt::Return { value, .. } => match value {
+            Some(value) => parenthesize("return", &[value]),
+            None => "(return)".to_string(),
+        },
+        Stmt::Block(statements) => {
+            let mut out = "(block".to_string();
+            for statement in statements {
+                out.push(' ');
+                out.push_str(&print_stmt(statement));
+            }
+            out.push(')');
+            out
+        }
+        Stmt::Class {
+            name,
+            superclass,
+            methods,
+            class_methods,
+        } => {
+            let mut out = format!("(class {}", name.lexeme);
+            if let Some(superclass) = superclass {
+                out.push_str(&format!(" < {}", print_expr(superclass)));
+            }
+            for method in methods {
+                out.push(' ');
+                let kind = if method.getter { "getter" } else { "method" };
+                out.push_str(&print_function(kind, method));
+            }
+            for method in class_methods {
+                out.push(' ');
+                out.push_str(&print_function("class method", method));
+            }
+            out.push(')');
+            out
+        }
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => match else_branch {
+            Some(else_branch) => format!(
+                "(if {} {} {})",
+                print_expr(condition),
+                print_stmt(then_branch),
+                print_stmt(else_branch)
+            ),
+            None => format!("(if {} {})", print_expr(condition), print_stmt(then_branch)),
+        },
+        Stmt::Switch {
+            subject,
+            cases,
+            default,
+        } => {
+            let mut out = format!("(switch {}", print_expr(subject));
+            for (value, body) in cases {
+                out.push_str(&format!(
+                    " (case {} {})",
+                    print_expr(value),
+                    print_stmt(body)
+                ));
+            }
+            if let Some(default) = default {
+                out.push_str(&format!(" (default {})", print_stmt(default)));
+            }
+            out.push(')');
+            out
+        }
+        Stmt::Var { name, initializer } => match initializer {
+            Some(initializer) => parenthesize(&format!("var {}", name.lexeme), &[initializer]),
+            None => format!("(var {})", name.lexeme),
+        },
+        Stmt::DoWhile { body, condition } => {
+            format!("(do {} {})", print_stmt(body), print_expr(condition))
+        }
+        Stmt::While {
+            condition,
+            body,
+            increment,
+        } => {
+            let mut out = format!("(while {} {}", print_expr(condition), print_stmt(body));
+            if let Some(increment) = increment {
+                out.push(' ');
+                out.push_str(&print_expr(increment));
+            }
+            out.push(')');
+            out
+        }
+    }
+}
+
+fn print_function(kind: &str, function: &FunctionDecl) -> String {
+    let mut params: Vec<String> = function
+        .params
+        .iter()
+        .map(|param| param.lexeme.clone())
+        .collect();
+    if function.variadic
+        && let Some(rest) = params.last_mut()
+    {
+        rest.insert_str(0, "...");
+    }
+    // Las funciones anónimas no tienen nombre que mostrar.
+    let mut out = if function.name.kind == TokenType::Fun {
+        format!("({kind} ({})", params.join(" "))
+    } else {
+        format!("({kind} {} ({})", function.name.lexeme, params.join(" "))
+    };
+    for statement in &function.body {
+        out.push(' ');
+        out.push_str(&print_stmt(statement));
+    }
+    out.push(')');
+    out
+}
+
+/// `=` o, en una asignación compuesta, `+=`, `-=`...
+fn assign_symbol(operator: &Option<Token>) -> String {
+    match operator {
+        Some(operator) => format!("{}=", operator.lexeme),
+        None => "=".to_string(),
+    }
+}
+
+fn parenthesize(name: &str, exprs: &[&Expr]) -> String {
+    let mut out = format!("({name}");
+    for expr in exprs {
+        out.push(' ');
+        out.push_str(&print_expr(expr));
+    }
+    out.push(')');
+    out
+}
@@ -0,0 +1,149 @@
+use std::{cell::RefCell, fmt, rc::Rc};
+
+use crate::{
+    class::LoxInstance,
+    environment::Environment,
+    interpreter::{FunctionDecl, Interpreter, RuntimeError, Unwind, Value},
+    token::{Token, TokenType},
+};
+
+pub trait LoxCallable: fmt::Display {
+    fn arity(&self) -> usize;
+
+    /// Máximo de argumentos aceptados; por defecto la aridad es fija.
+    fn max_arity(&self) -> usize {
+        self.arity()
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        paren: &Token,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError>;
+}
+
+type NativeFn = fn(&mut Interpreter, &Token, Vec<Value>) -> Result<Value, RuntimeError>;
+
+pub struct NativeFunction {
+    pub name: &'static str,
+    pub arity: usize,
+    pub max_arity: usize,
+    pub function: NativeFn,
+}
+
+impl LoxCallable for NativeFunction {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn max_arity(&self) -> usize {
+        self.max_arity
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        paren: &Token,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        (self.function)(interpreter, paren, arguments)
+    }
+}
+
+impl fmt::Display for NativeFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<native fn>")
+    }
+}
+
+pub struct LoxFunction {
+    declaration: Rc<FunctionDecl>,
+    closure: Rc<RefCell<Environment>>,
+    is_initializer: bool,
+}
+
+impl LoxFunction {
+    pub fn new(
+        declaration: Rc<FunctionDecl>,
+        closure: Rc<RefCell<Environment>>,
+        is_initializer: bool,
+    ) -> Self {
+        Self {
+            declaration,
+            closure,
+            is_initializer,
+        }
+    }
+
+    pub fn is_getter(&self) -> bool {
+        self.declaration.getter
+    }
+
+    pub fn bind(&self, instance: Rc<RefCell<LoxInstance>>) -> LoxFunction {
+        let mut environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        environment.define("this", Value::Instance(instance));
+        LoxFunction::new(
+            Rc::clone(&self.declaration),
+            Rc::new(RefCell::new(environment)),
+            self.is_initializer,
+        )
+    }
+}
+
+impl LoxCallable for LoxFunction {
+    fn arity(&self) -> usize {
+        self.declaration.params.len() - usize::from(self.declaration.variadic)
+    }
+
+    fn max_arity(&self) -> usize {
+        if self.declaration.variadic {
+            usize::MAX
+        } else {
+            self.arity()
+        }
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        paren: &Token,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let mut environment = Environment::with_enclosing(Rc::clone(&self.closure));
+        let mut arguments = arguments.into_iter();
+        for param in &self.declaration.params[..self.arity()] {
+            environment.define(&param.lexeme, arguments.next().unwrap());
+        }
+        if let Some(rest) = self.declaration.params.last()
+            && self.declaration.variadic
+        {
+            let rest_value = Value::List(Rc::new(RefCell::new(arguments.collect())));
+            environment.define(&rest.lexeme, rest_value);
+        }
+
+        let value = match interpreter.execute_block(&self.declaration.body, environment) {
+            // El parser impide que `break`/`continue` salgan de una función.
+            Ok(()) | Err(Unwind::Break | Unwind::Continue) => Value::Nil,
+            Err(Unwind::Return(value)) => value,
+            Err(Unwind::RuntimeError(error)) => return Err(error),
+        };
+
+        // `init` siempre devuelve la instancia, incluso con un `return;` explícito.
+        if self.is_initializer {
+            let this = Token::new(TokenType::This, "this", None, paren.line, paren.column);
+            return self.closure.borrow().get_at(0, &this);
+        }
+        Ok(value)
+    }
+}
+
+impl fmt::Display for LoxFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.declaration.name.kind == TokenType::Fun {
+            write!(f, "<fn>")
+        } else {
+            write!(f, "<fn {}>", self.declaration.name.lexeme)
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::{cell::RefCell, collections::HashMap, fmt, rc::Rc};
+
+use crate::{
+    callable::{LoxCallable, LoxFunction},
+    interpreter::{Interpreter, RuntimeError, Value},
+    token::Token,
+};
+
+pub struct LoxClass {
+    pub name: String,
+    superclass: Option<Rc<LoxClass>>,
+    methods: HashMap<String, Rc<LoxFunction>>,
+    class_methods: HashMap<String, Rc<LoxFunction>>,
+}
+
+impl LoxClass {
+    pub fn new(
+        name: impl Into<String>,
+        superclass: Option<Rc<LoxClass>>,
+        methods: HashMap<String, Rc<LoxFunction>>,
+        class_methods: HashMap<String, Rc<LoxFunction>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            superclass,
+            methods,
+            class_methods,
+        }
+    }
+
+    pub fn find_method(&self, name: &str) -> Option<Rc<LoxFunction>> {
+        if let Some(method) = self.methods.get(name) {
+            return Some(Rc::clone(method));
+        }
+        self.superclass
+            .as_ref()
+            .and_then(|superclass| superclass.find_method(name))
+    }
+
+    /// Método de clase (declarado con `class`); se hereda igual que los de instancia.
+    pub fn find_class_method(&self, name: &str) -> Option<Rc<LoxFunction>> {
+        if let Some(method) = self.class_methods.get(name) {
+            return Some(Rc::clone(method));
+        }
+        self.superclass
+            .as_ref()
+            .and_then(|superclass| superclass.find_class_method(name))
+    }
+}
+
+impl LoxCallable for Rc<LoxClass> {
+    fn arity(&self) -> usize {
+        self.find_method("init")
+            .map(|initializer| initializer.arity())
+            .unwrap_or(0)
+    }
+
+    fn max_arity(&self) -> usize {
+        self.find_method("init")
+            .map(|initializer| initializer.max_arity())
+            .unwrap_or(0)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        paren: &Token,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let instance = Rc::new(RefCell::new(LoxInstance::new(Rc::clone(self))));
+        if let Some(initializer) = self.find_method("init") {
+            initializer
+                .bind(Rc::clone(&instance))
+                .call(interpreter, paren, arguments)?;
+        }
+        Ok(Value::Instance(instance))
+    }
+}
+
+impl fmt::Display for LoxClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+pub struct LoxInstance {
+    class: Rc<LoxClass>,
+    fields: HashMap<String, Value>,
+}
+
+impl LoxInstance {
+    pub fn new(class: Rc<LoxClass>) -> Self {
+        Self {
+            class,
+            fields: HashMap::new(),
+        }
+    }
+
+    pub fn get(instance: &Rc<RefCell<LoxInstance>>, name: &Token) -> Result<Value, RuntimeError> {
+        let this = instance.borrow();
+        if let Some(value) = this.fields.get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        if let Some(method) = this.class.find_method(&name.lexeme) {
+            let bound = method.bind(Rc::clone(instance));
+            return Ok(Value::Callable(Rc::new(bound)));
+        }
+
+        Err(RuntimeError::new(
+            name.clone(),
+            &format!("Undefined property '{}'.", name.lexeme),
+        ))
+    }
+
+    /// Método de la clase de `instance`, ya ligado a ella.
+    pub fn bound_method(instance: &Rc<RefCell<LoxInstance>>, name: &str) -> Option<LoxFunction> {
+        let method = instance.borrow().class.find_method(name)?;
+        Some(method.bind(Rc::clone(instance)))
+    }
+
+    /// Getter de la clase ligado a `instance`, salvo que un campo con ese nombre lo oculte.
+    pub fn getter(instance: &Rc<RefCell<LoxInstance>>, name: &str) -> Option<LoxFunction> {
+        if instance.borrow().fields.contains_key(name) {
+            return None;
+        }
+        Self::bound_method(instance, name).filter(LoxFunction::is_getter)
+    }
+
+    pub fn set(&mut self, name: &Token, value: Value) {
+        self.fields.insert(name.lexeme.clone(), value);
+    }
+}
+
+impl fmt::Display for LoxInstance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} instance", self.class.name)
+    }
+}
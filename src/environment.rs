@@ -0,0 +1,94 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::{
+    interpreter::{RuntimeError, Value},
+    token::Token,
+};
+
+#[derive(Default)]
+pub struct Environment {
+    values: HashMap<String, Value>,
+    enclosing: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_enclosing(enclosing: Rc<RefCell<Environment>>) -> Self {
+        Self {
+            values: HashMap::new(),
+            enclosing: Some(enclosing),
+        }
+    }
+
+    pub fn define(&mut self, name: impl Into<String>, value: Value) {
+        self.values.insert(name.into(), value);
+    }
+
+    pub fn get(&self, name: &Token) -> Result<Value, RuntimeError> {
+        if let Some(value) = self.values.get(&name.lexeme) {
+            return Ok(value.clone());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow().get(name),
+            None => Err(Self::undefined(name)),
+        }
+    }
+
+    pub fn assign(&mut self, name: &Token, value: Value) -> Result<(), RuntimeError> {
+        if let Some(slot) = self.values.get_mut(&name.lexeme) {
+            *slot = value;
+            return Ok(());
+        }
+
+        match &self.enclosing {
+            Some(enclosing) => enclosing.borrow_mut().assign(name, value),
+            None => Err(Self::undefined(name)),
+        }
+    }
+
+    pub fn get_at(&self, distance: usize, name: &Token) -> Result<Value, RuntimeError> {
+        if distance == 0 {
+            return self
+                .values
+                .get(&name.lexeme)
+                .cloned()
+                .ok_or_else(|| Self::undefined(name));
+        }
+        self.ancestor(distance).borrow().get_at(0, name)
+    }
+
+    pub fn assign_at(
+        &mut self,
+        distance: usize,
+        name: &Token,
+        value: Value,
+    ) -> Result<(), RuntimeError> {
+        if distance == 0 {
+            self.values.insert(name.lexeme.clone(), value);
+            return Ok(());
+        }
+        self.ancestor(distance)
+            .borrow_mut()
+            .assign_at(0, name, value)
+    }
+
+    fn ancestor(&self, distance: usize) -> Rc<RefCell<Environment>> {
+        let mut environment = Rc::clone(self.enclosing.as_ref().unwrap());
+        for _ in 1..distance {
+            let enclosing = Rc::clone(environment.borrow().enclosing.as_ref().unwrap());
+            environment = enclosing;
+        }
+        environment
+    }
+
+    fn undefined(name: &Token) -> RuntimeError {
+        RuntimeError::new(
+            name.clone(),
+            &format!("Undefined variable '{}'.", name.lexeme),
+        )
+    }
+}
@@ -1,25 +1,186 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    io::{self, Write},
+    path::PathBuf,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
 use crate::{
+    callable::{LoxCallable, LoxFunction},
+    class::{LoxClass, LoxInstance},
+    environment::Environment,
     lox::Lox,
+    natives,
+    resolver::Resolver,
     token::{Literal, Token, TokenType},
 };
 
+/// Identificador único de una expresión, usado por el resolver.
+pub type ExprId = usize;
+
+pub fn next_expr_id() -> ExprId {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+#[derive(Clone)]
 pub enum Expr {
+    Assign {
+        id: ExprId,
+        name: Token,
+        value: Box<Expr>,
+    },
     Binary {
         left: Box<Expr>,
         operator: Token,
         right: Box<Expr>,
     },
+    Call {
+        callee: Box<Expr>,
+        paren: Token,
+        arguments: Vec<Expr>,
+    },
+    Get {
+        object: Box<Expr>,
+        name: Token,
+    },
     Grouping(Box<Expr>),
-    Literal(Literal),
+    Index {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+    },
+    /// Con `operator`, asignación compuesta (`a[i] += e`): objeto e índice se evalúan
+    /// una sola vez.
+    IndexSet {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+        operator: Option<Token>,
+        value: Box<Expr>,
+    },
+    /// Función anónima: `fun (a, b) { ... }`. Su nombre es el token `fun`.
+    Lambda(Rc<FunctionDecl>),
+    List(Vec<Expr>),
+    Literal(Value),
+    Logical {
+        left: Box<Expr>,
+        operator: Token,
+        right: Box<Expr>,
+    },
+    Map {
+        brace: Token,
+        entries: Vec<(Expr, Expr)>,
+    },
+    Postfix {
+        operand: Box<Expr>,
+        operator: Token,
+    },
+    Prefix {
+        operator: Token,
+        operand: Box<Expr>,
+    },
+    /// Con `operator`, asignación compuesta (`a.b += e`).
+    Set {
+        object: Box<Expr>,
+        name: Token,
+        operator: Option<Token>,
+        value: Box<Expr>,
+    },
+    /// `object[start..end]`, sin incluir `end`.
+    Slice {
+        object: Box<Expr>,
+        bracket: Token,
+        start: Box<Expr>,
+        end: Box<Expr>,
+    },
+    Super {
+        id: ExprId,
+        keyword: Token,
+        method: Token,
+    },
+    Ternary {
+        condition: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+    },
+    This {
+        id: ExprId,
+        keyword: Token,
+    },
     Unary {
         operator: Token,
         right: Box<Expr>,
     },
+    Variable {
+        id: ExprId,
+        name: Token,
+    },
+}
+
+pub struct FunctionDecl {
+    pub name: Token,
+    pub params: Vec<Token>,
+    /// El último parámetro (`...resto`) recibe en una lista los argumentos sobrantes.
+    pub variadic: bool,
+    /// Método declarado sin parámetros (`area { ... }`) que se llama al leerlo.
+    pub getter: bool,
+    pub body: Vec<Stmt>,
 }
 
 pub enum Stmt {
+    Break(Token),
+    Continue(Token),
     Expression(Expr),
-    Print(Expr),
+    Function(Rc<FunctionDecl>),
+    Import {
+        keyword: Token,
+        path: Token,
+    },
+    Print {
+        expr: Expr,
+        newline: bool,
+    },
+    Return {
+        keyword: Token,
+        value: Option<Expr>,
+    },
+    Block(Vec<Stmt>),
+    Class {
+        name: Token,
+        superclass: Option<Expr>,
+        methods: Vec<Rc<FunctionDecl>>,
+        /// Métodos declarados con `class`, que se llaman sobre la propia clase.
+        class_methods: Vec<Rc<FunctionDecl>>,
+    },
+    If {
+        condition: Expr,
+        then_branch: Box<Stmt>,
+        else_branch: Option<Box<Stmt>>,
+    },
+    /// Cada rama es un `Stmt::Block`; solo se ejecuta la primera que coincide.
+    Switch {
+        subject: Expr,
+        cases: Vec<(Expr, Stmt)>,
+        default: Option<Box<Stmt>>,
+    },
+    Var {
+        name: Token,
+        initializer: Option<Expr>,
+    },
+    /// `do cuerpo while (condición);`: el cuerpo se ejecuta al menos una vez.
+    DoWhile {
+        body: Box<Stmt>,
+        condition: Expr,
+    },
+    While {
+        condition: Expr,
+        body: Box<Stmt>,
+        increment: Option<Expr>,
+    },
 }
 
 #[derive(Clone)]
@@ -27,9 +188,60 @@ pub enum Value {
     Nil,
     Bool(bool),
     Number(f64),
-    Str(String),
+    Str(Rc<str>),
+    Callable(Rc<dyn LoxCallable>),
+    Class(Rc<LoxClass>),
+    Instance(Rc<RefCell<LoxInstance>>),
+    List(Rc<RefCell<Vec<Value>>>),
+    Map(Rc<RefCell<HashMap<String, Value>>>),
 }
 
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Nil => write!(f, "nil"),
+            Value::Bool(b) => write!(f, "{b}"),
+            Value::Number(n) if n.is_nan() => write!(f, "NaN"),
+            Value::Number(n) if n.is_infinite() => {
+                write!(f, "{}Infinity", if *n < 0.0 { "-" } else { "" })
+            }
+            // Evita imprimir "-0".
+            Value::Number(n) if *n == 0.0 => write!(f, "0"),
+            // `f64` no agrega ".0" a los enteros al formatearse.
+            Value::Number(n) => write!(f, "{n}"),
+            Value::Str(s) => write!(f, "{s}"),
+            Value::Callable(callable) => write!(f, "{callable}"),
+            Value::Class(class) => write!(f, "{class}"),
+            Value::Instance(instance) => write!(f, "{}", instance.borrow()),
+            Value::List(list) => {
+                write!(f, "[")?;
+                for (i, element) in list.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                write!(f, "]")
+            }
+            Value::Map(map) => {
+                // Las claves se ordenan para que la salida sea estable.
+                let map = map.borrow();
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                write!(f, "{{")?;
+                for (i, key) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{key}: {}", map[key])?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct RuntimeError {
     pub token: Token,
     pub message: String,
@@ -44,47 +256,555 @@ impl RuntimeError {
     }
 }
 
-pub struct Interpreter;
+/// Motivos por los que la ejecución de una sentencia se interrumpe.
+pub enum Unwind {
+    RuntimeError(RuntimeError),
+    Return(Value),
+    Break,
+    Continue,
+}
+
+impl From<RuntimeError> for Unwind {
+    fn from(error: RuntimeError) -> Self {
+        Unwind::RuntimeError(error)
+    }
+}
+
+pub struct Interpreter {
+    globals: Rc<RefCell<Environment>>,
+    environment: Rc<RefCell<Environment>>,
+    locals: HashMap<ExprId, usize>,
+    out: Box<dyn Write>,
+    call_depth: usize,
+    /// Rutas (canónicas) de los módulos ya importados.
+    imported: HashSet<PathBuf>,
+    /// Directorio del script en ejecución: las rutas relativas de `import` parten de él.
+    pub(crate) script_dir: PathBuf,
+    /// Cantidad máxima de llamadas anidadas antes de informar "Stack overflow.". El
+    /// valor por defecto supone un hilo con `STACK_SIZE` de pila.
+    pub max_call_depth: usize,
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Interpreter {
     pub fn new() -> Self {
-        Interpreter
+        Self::with_output(Box::new(io::stdout()))
+    }
+
+    /// Crea un intérprete que escribe la salida de `print` en `out`.
+    pub fn with_output(out: Box<dyn Write>) -> Self {
+        let mut globals = Environment::new();
+        natives::define_globals(&mut globals);
+        let globals = Rc::new(RefCell::new(globals));
+        Interpreter {
+            environment: Rc::clone(&globals),
+            globals,
+            locals: HashMap::new(),
+            out,
+            call_depth: 0,
+            imported: HashSet::new(),
+            script_dir: PathBuf::new(),
+            max_call_depth: 1000,
+        }
+    }
+
+    /// Destino de la salida del programa.
+    pub fn output(&mut self) -> &mut dyn Write {
+        self.out.as_mut()
+    }
+
+    pub fn resolve(&mut self, id: ExprId, depth: usize) {
+        self.locals.insert(id, depth);
     }
 
     pub fn interpret(&mut self, lox: &mut Lox, statements: Vec<Stmt>) {
+        self.interpret_with(lox, statements, false);
+    }
+
+    /// Como `interpret`, pero muestra el valor de las sentencias de expresión de
+    /// nivel superior, como espera el usuario del REPL.
+    pub fn interpret_repl(&mut self, lox: &mut Lox, statements: Vec<Stmt>) {
+        self.interpret_with(lox, statements, true);
+    }
+
+    /// Ejecuta las sentencias hasta el primer error de ejecución y lo devuelve, sin
+    /// informarlo. Pensado para quien integra el intérprete; las sentencias deben
+    /// haber pasado por el `Resolver` con este mismo intérprete.
+    pub fn try_interpret(&mut self, statements: &[Stmt]) -> Result<(), RuntimeError> {
+        for statement in statements {
+            if let Err(Unwind::RuntimeError(error)) = self.execute(statement) {
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
+    fn interpret_with(&mut self, lox: &mut Lox, statements: Vec<Stmt>, echo: bool) {
         for statement in statements.iter() {
-            match self.execute(statement) {
+            let result = match statement {
+                Stmt::Expression(expr) if echo => match self.evaluate(expr) {
+                    Ok(value) => {
+                        // Un `nil` implícito (p. ej. el de una función sin `return`)
+                        // no se muestra; uno escrito por el usuario sí.
+                        let explicit_nil = matches!(expr, Expr::Literal(Value::Nil));
+                        if !matches!(value, Value::Nil) || explicit_nil {
+                            writeln!(self.out, "{value}").unwrap();
+                        }
+                        Ok(())
+                    }
+                    Err(error) => Err(error.into()),
+                },
+                _ => self.execute(statement),
+            };
+            match result {
                 Ok(()) => {}
-                Err(error) => lox.runtime_error(error),
+                Err(Unwind::RuntimeError(error)) => {
+                    lox.runtime_error(error);
+                    if lox.stop_on_error {
+                        break;
+                    }
+                }
+                Err(_) => {}
             };
         }
-
-        // match self.evaluate(&expr) {
-        //     Ok(value) => {
-        //         println!("{}", self.stringify(value));
-        //     }
-        //     Err(error) => lox.runtime_error(error),
-        // };
     }
 
-    fn execute(&mut self, stmt: &Stmt) -> Result<(), RuntimeError> {
+    fn execute(&mut self, stmt: &Stmt) -> Result<(), Unwind> {
         match stmt {
+            Stmt::Break(_) => Err(Unwind::Break),
+            Stmt::Continue(_) => Err(Unwind::Continue),
             Stmt::Expression(expr) => match self.evaluate(expr) {
                 Ok(_) => Ok(()),
-                Err(error) => Err(error),
+                Err(error) => Err(error.into()),
             },
-            Stmt::Print(expr) => {
+            Stmt::Function(declaration) => {
+                let function =
+                    LoxFunction::new(Rc::clone(declaration), Rc::clone(&self.environment), false);
+                self.environment
+                    .borrow_mut()
+                    .define(&declaration.name.lexeme, Value::Callable(Rc::new(function)));
+                Ok(())
+            }
+            Stmt::Print { expr, newline } => {
                 let value = self.evaluate(expr)?;
-                println!("{}", self.stringify(value));
+                if *newline {
+                    writeln!(self.out, "{value}").unwrap();
+                } else {
+                    write!(self.out, "{value}").unwrap();
+                    self.out.flush().unwrap();
+                }
+                Ok(())
+            }
+            Stmt::Return { value, .. } => {
+                let value = match value {
+                    Some(expr) => self.evaluate(expr)?,
+                    None => Value::Nil,
+                };
+                Err(Unwind::Return(value))
+            }
+            Stmt::Block(statements) => {
+                let environment = Environment::with_enclosing(Rc::clone(&self.environment));
+                self.execute_block(statements, environment)
+            }
+            Stmt::Class {
+                name,
+                superclass,
+                methods,
+                class_methods,
+            } => {
+                let superclass = match superclass {
+                    Some(expr) => match self.evaluate(expr)? {
+                        Value::Class(class) => Some(class),
+                        _ => {
+                            let token = match expr {
+                                Expr::Variable { name, .. } => name,
+                                _ => name,
+                            };
+                            let error =
+                                RuntimeError::new(token.clone(), "Superclass must be a class.");
+                            return Err(error.into());
+                        }
+                    },
+                    None => None,
+                };
+
+                let enclosing = Rc::clone(&self.environment);
+                if let Some(superclass) = &superclass {
+                    let mut environment = Environment::with_enclosing(Rc::clone(&enclosing));
+                    environment.define("super", Value::Class(Rc::clone(superclass)));
+                    self.environment = Rc::new(RefCell::new(environment));
+                }
+
+                let methods = methods
+                    .iter()
+                    .map(|method| {
+                        let function = LoxFunction::new(
+                            Rc::clone(method),
+                            Rc::clone(&self.environment),
+                            method.name.lexeme == "init",
+                        );
+                        (method.name.lexeme.clone(), Rc::new(function))
+                    })
+                    .collect();
+                let class_methods = class_methods
+                    .iter()
+                    .map(|method| {
+                        let function = LoxFunction::new(
+                            Rc::clone(method),
+                            Rc::clone(&self.environment),
+                            false,
+                        );
+                        (method.name.lexeme.clone(), Rc::new(function))
+                    })
+                    .collect();
+                let class = LoxClass::new(&name.lexeme, superclass, methods, class_methods);
+                self.environment = enclosing;
+                self.environment
+                    .borrow_mut()
+                    .define(&name.lexeme, Value::Class(Rc::new(class)));
+                Ok(())
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let condition = self.evaluate(condition)?;
+                if self.is_truthy(condition) {
+                    self.execute(then_branch)
+                } else if let Some(else_branch) = else_branch {
+                    self.execute(else_branch)
+                } else {
+                    Ok(())
+                }
+            }
+            Stmt::Switch {
+                subject,
+                cases,
+                default,
+            } => {
+                let subject = self.evaluate(subject)?;
+                for (value, body) in cases {
+                    let value = self.evaluate(value)?;
+                    if self.is_equal(subject.clone(), value) {
+                        return self.execute(body);
+                    }
+                }
+                match default {
+                    Some(default) => self.execute(default),
+                    None => Ok(()),
+                }
+            }
+            Stmt::Import { keyword, path } => self.import(keyword, path),
+            Stmt::Var { name, initializer } => {
+                let value = match initializer {
+                    Some(expr) => self.evaluate(expr)?,
+                    None => Value::Nil,
+                };
+                self.environment.borrow_mut().define(&name.lexeme, value);
                 Ok(())
             }
+            Stmt::DoWhile { body, condition } => {
+                loop {
+                    match self.execute(body) {
+                        Ok(()) | Err(Unwind::Continue) => {}
+                        Err(Unwind::Break) => break,
+                        Err(unwind) => return Err(unwind),
+                    }
+                    let value = self.evaluate(condition)?;
+                    if !self.is_truthy(value) {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            Stmt::While {
+                condition,
+                body,
+                increment,
+            } => {
+                loop {
+                    let value = self.evaluate(condition)?;
+                    if !self.is_truthy(value) {
+                        break;
+                    }
+                    match self.execute(body) {
+                        Ok(()) | Err(Unwind::Continue) => {}
+                        Err(Unwind::Break) => break,
+                        Err(unwind) => return Err(unwind),
+                    }
+                    if let Some(increment) = increment {
+                        self.evaluate(increment)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Ejecuta un módulo en el entorno global. Cada archivo se carga una sola vez,
+    /// lo que también corta los imports circulares.
+    fn import(&mut self, keyword: &Token, path: &Token) -> Result<(), Unwind> {
+        let Some(Literal::Str(name)) = &path.literal else {
+            unreachable!("la ruta de 'import' siempre es una cadena");
+        };
+        let cannot_import =
+            || RuntimeError::new(keyword.clone(), &format!("Could not import '{name}'."));
+
+        let canonical =
+            fs::canonicalize(self.script_dir.join(name)).map_err(|_| cannot_import())?;
+        if !self.imported.insert(canonical.clone()) {
+            return Ok(());
+        }
+        let source = fs::read_to_string(&canonical).map_err(|_| cannot_import())?;
+
+        let mut lox = Lox::with_output(Box::new(io::sink()), Box::new(io::sink()));
+        let statements = lox.parse(&source);
+        if !lox.had_error {
+            Resolver::new(self, &mut lox).resolve(&statements);
+        }
+        // Los avisos del módulo no impiden importarlo.
+        if lox.had_error {
+            let diagnostic = lox.diagnostics.iter().find(|d| !d.warning).unwrap();
+            let message = format!("Error in module '{name}': {diagnostic}");
+            return Err(RuntimeError::new(keyword.clone(), &message).into());
         }
+
+        // Los `import` del módulo se resuelven desde su propio directorio.
+        let module_dir = canonical.parent().unwrap().to_path_buf();
+        let previous_dir = std::mem::replace(&mut self.script_dir, module_dir);
+        let previous = std::mem::replace(&mut self.environment, Rc::clone(&self.globals));
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.execute(statement));
+        self.environment = previous;
+        self.script_dir = previous_dir;
+        result
+    }
+
+    pub fn execute_block(
+        &mut self,
+        statements: &[Stmt],
+        environment: Environment,
+    ) -> Result<(), Unwind> {
+        let previous = std::mem::replace(&mut self.environment, Rc::new(RefCell::new(environment)));
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.execute(statement));
+        // Restaurar siempre el entorno anterior, incluso si hubo un error.
+        self.environment = previous;
+        result
     }
 
     fn evaluate(&mut self, expr: &Expr) -> Result<Value, RuntimeError> {
         match expr {
-            Expr::Literal(value) => Ok(value.clone().into()),
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Assign { id, name, value } => {
+                let value = self.evaluate(value)?;
+                self.assign_variable(*id, name, value.clone())?;
+                Ok(value)
+            }
+            Expr::Postfix { operand, operator } => {
+                let (old, _) = self.increment(operand, operator)?;
+                Ok(Value::Number(old))
+            }
+            Expr::Prefix { operator, operand } => {
+                let (_, new) = self.increment(operand, operator)?;
+                Ok(Value::Number(new))
+            }
             Expr::Grouping(inner) => self.evaluate(inner),
+            Expr::Super {
+                id,
+                keyword,
+                method,
+            } => {
+                let distance = self.locals[id];
+                let Value::Class(superclass) =
+                    self.environment.borrow().get_at(distance, keyword)?
+                else {
+                    unreachable!("'super' siempre es una clase");
+                };
+                let this = Token::new(TokenType::This, "this", None, keyword.line, keyword.column);
+                let Value::Instance(object) =
+                    self.environment.borrow().get_at(distance - 1, &this)?
+                else {
+                    unreachable!("'this' siempre es una instancia");
+                };
+
+                match superclass.find_method(&method.lexeme) {
+                    Some(function) if function.is_getter() => {
+                        self.call_function(&function.bind(object), method, Vec::new())
+                    }
+                    Some(function) => Ok(Value::Callable(Rc::new(function.bind(object)))),
+                    None => Err(RuntimeError::new(
+                        method.clone(),
+                        &format!("Undefined property '{}'.", method.lexeme),
+                    )),
+                }
+            }
+            Expr::Lambda(declaration) => {
+                let function =
+                    LoxFunction::new(Rc::clone(declaration), Rc::clone(&self.environment), false);
+                Ok(Value::Callable(Rc::new(function)))
+            }
+            Expr::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                let condition = self.evaluate(condition)?;
+                if self.is_truthy(condition) {
+                    self.evaluate(then_branch)
+                } else {
+                    self.evaluate(else_branch)
+                }
+            }
+            Expr::This { id, keyword } => self.look_up_variable(*id, keyword),
+            Expr::Variable { id, name } => self.look_up_variable(*id, name),
+            Expr::Call {
+                callee,
+                paren,
+                arguments,
+            } => {
+                let callee = self.evaluate(callee)?;
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| self.evaluate(argument))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let function: &dyn LoxCallable = match &callee {
+                    Value::Callable(function) => function.as_ref(),
+                    Value::Class(class) => class,
+                    _ => {
+                        return Err(RuntimeError::new(
+                            paren.clone(),
+                            "Can only call functions and classes.",
+                        ));
+                    }
+                };
+                self.call_function(function, paren, arguments)
+            }
+            Expr::Get { object, name } => match self.evaluate(object)? {
+                Value::Instance(instance) => match LoxInstance::getter(&instance, &name.lexeme) {
+                    Some(getter) => self.call_function(&getter, name, Vec::new()),
+                    None => LoxInstance::get(&instance, name),
+                },
+                Value::Class(class) => match class.find_class_method(&name.lexeme) {
+                    Some(method) if method.is_getter() => {
+                        self.call_function(method.as_ref(), name, Vec::new())
+                    }
+                    Some(method) => Ok(Value::Callable(method)),
+                    None => Err(RuntimeError::new(
+                        name.clone(),
+                        &format!("Undefined class method '{}'.", name.lexeme),
+                    )),
+                },
+                _ => Err(RuntimeError::new(
+                    name.clone(),
+                    "Only instances have properties.",
+                )),
+            },
+            Expr::Set {
+                object,
+                name,
+                operator,
+                value,
+            } => {
+                let Value::Instance(instance) = self.evaluate(object)? else {
+                    return Err(RuntimeError::new(
+                        name.clone(),
+                        "Only instances have fields.",
+                    ));
+                };
+                let value = match operator {
+                    Some(operator) => {
+                        let current = LoxInstance::get(&instance, name)?;
+                        let right = self.evaluate(value)?;
+                        self.binary(current, operator, right)?
+                    }
+                    None => self.evaluate(value)?,
+                };
+                instance.borrow_mut().set(name, value.clone());
+                Ok(value)
+            }
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                self.get_index(&object, &index, bracket)
+            }
+            Expr::Slice {
+                object,
+                bracket,
+                start,
+                end,
+            } => {
+                let object = self.evaluate(object)?;
+                let start = self.evaluate(start)?;
+                let end = self.evaluate(end)?;
+                self.slice(&object, &start, &end, bracket)
+            }
+            Expr::IndexSet {
+                object,
+                bracket,
+                index,
+                operator,
+                value,
+            } => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                let value = match operator {
+                    Some(operator) => {
+                        let current = self.get_index(&object, &index, bracket)?;
+                        let right = self.evaluate(value)?;
+                        self.binary(current, operator, right)?
+                    }
+                    None => self.evaluate(value)?,
+                };
+                self.set_index(&object, index, value.clone(), bracket)?;
+                Ok(value)
+            }
+            Expr::List(elements) => {
+                let mut values = Vec::with_capacity(elements.len());
+                for element in elements {
+                    values.push(self.evaluate(element)?);
+                }
+                Ok(Value::List(Rc::new(RefCell::new(values))))
+            }
+            Expr::Map { brace, entries } => {
+                let mut map = HashMap::with_capacity(entries.len());
+                for (key, value) in entries {
+                    let key = self.evaluate(key)?;
+                    let key = self.map_key(key, brace)?;
+                    let value = self.evaluate(value)?;
+                    map.insert(key, value);
+                }
+                Ok(Value::Map(Rc::new(RefCell::new(map))))
+            }
+            Expr::Logical {
+                left,
+                operator,
+                right,
+            } => {
+                let left = self.evaluate(left)?;
+                // Cortocircuito: se devuelve el operando izquierdo sin evaluar el derecho.
+                if operator.kind == TokenType::Or {
+                    if self.is_truthy(left.clone()) {
+                        return Ok(left);
+                    }
+                } else if !self.is_truthy(left.clone()) {
+                    return Ok(left);
+                }
+                self.evaluate(right)
+            }
             Expr::Unary { operator, right } => {
                 let right = self.evaluate(right)?;
 
@@ -96,6 +816,10 @@ impl Interpreter {
                             Err(self.number_operand_error(operator))
                         }
                     }
+                    TokenType::Plus => match right {
+                        Value::Number(_) => Ok(right),
+                        _ => Err(self.number_operand_error(operator)),
+                    },
                     TokenType::Bang => Ok(Value::Bool(!self.is_truthy(right))),
                     _ => Ok(Value::Nil),
                 }
@@ -108,77 +832,228 @@ impl Interpreter {
                 let left = self.evaluate(left)?;
                 let right = self.evaluate(right)?;
 
-                match operator.kind {
-                    TokenType::Minus => match (left, right) {
-                        (Value::Number(left), Value::Number(right)) => {
-                            Ok(Value::Number(left - right))
-                        }
-                        _ => Err(self.number_operands_error(operator)),
-                    },
+                self.binary(left, operator, right)
+            }
+        }
+    }
 
-                    TokenType::Plus => match (left, right) {
-                        (Value::Number(left), Value::Number(right)) => {
-                            Ok(Value::Number(left + right))
-                        }
-                        (Value::Str(left), Value::Str(right)) => Ok(Value::Str(left + &right)),
-                        _ => {
-                            let error = RuntimeError::new(
+    /// Aplica un operador binario a dos valores ya evaluados.
+    fn binary(
+        &mut self,
+        left: Value,
+        operator: &Token,
+        right: Value,
+    ) -> Result<Value, RuntimeError> {
+        if let Value::Instance(instance) = &left
+            && let Some(name) = operator_method(operator.kind)
+            && let Some(method) = LoxInstance::bound_method(instance, name)
+        {
+            let result = self.call_function(&method, operator, vec![right])?;
+            return if operator.kind == TokenType::BangEqual {
+                Ok(Value::Bool(!self.is_truthy(result)))
+            } else {
+                Ok(result)
+            };
+        }
+
+        match operator.kind {
+            TokenType::Minus => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left - right)),
+                _ => Err(self.number_operands_error(operator)),
+            },
+
+            TokenType::Plus => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left + right)),
+                (Value::Str(left), Value::Str(right)) => {
+                    Ok(Value::Str(format!("{left}{right}").into()))
+                }
+                (Value::Str(left), right @ (Value::Number(_) | Value::Bool(_))) => {
+                    Ok(Value::Str(format!("{left}{right}").into()))
+                }
+                (left @ (Value::Number(_) | Value::Bool(_)), Value::Str(right)) => {
+                    Ok(Value::Str(format!("{left}{right}").into()))
+                }
+                _ => {
+                    let error = RuntimeError::new(
+                        operator.clone(),
+                        "Operands must be two numbers or two strings.",
+                    );
+                    Err(error)
+                }
+            },
+
+            TokenType::Slash => match (left, right) {
+                (Value::Number(_), Value::Number(0.0)) => {
+                    Err(RuntimeError::new(operator.clone(), "Division by zero."))
+                }
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left / right)),
+                _ => Err(self.number_operands_error(operator)),
+            },
+
+            TokenType::Star => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left * right)),
+                _ => Err(self.number_operands_error(operator)),
+            },
+
+            TokenType::Percent => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left % right)),
+                _ => Err(self.number_operands_error(operator)),
+            },
+
+            TokenType::StarStar => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Number(left.powf(right))),
+                _ => Err(self.number_operands_error(operator)),
+            },
+
+            TokenType::Ampersand
+            | TokenType::Pipe
+            | TokenType::Caret
+            | TokenType::LessLess
+            | TokenType::GreaterGreater => {
+                let (left, right) = self.integer_operands(operator, left, right)?;
+                let result = match operator.kind {
+                    TokenType::Ampersand => left & right,
+                    TokenType::Pipe => left | right,
+                    TokenType::Caret => left ^ right,
+                    _ => {
+                        let Some(shift) = u32::try_from(right).ok().filter(|s| *s < 64) else {
+                            return Err(RuntimeError::new(
                                 operator.clone(),
-                                "Operands must be two numbers or two strings.",
-                            );
-                            Err(error)
+                                "Shift amount must be between 0 and 63.",
+                            ));
+                        };
+                        if operator.kind == TokenType::LessLess {
+                            left << shift
+                        } else {
+                            left >> shift
                         }
-                    },
+                    }
+                };
+                Ok(Value::Number(result as f64))
+            }
 
-                    TokenType::Slash => match (left, right) {
-                        (Value::Number(left), Value::Number(right)) => {
-                            Ok(Value::Number(left / right))
-                        }
-                        _ => Err(self.number_operands_error(operator)),
-                    },
+            TokenType::Greater => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Bool(left > right)),
+                (Value::Str(left), Value::Str(right)) => Ok(Value::Bool(left > right)),
+                _ => Err(self.number_operands_error(operator)),
+            },
 
-                    TokenType::Star => match (left, right) {
-                        (Value::Number(left), Value::Number(right)) => {
-                            Ok(Value::Number(left * right))
-                        }
-                        _ => Err(self.number_operands_error(operator)),
-                    },
+            TokenType::GreaterEqual => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Bool(left >= right)),
+                (Value::Str(left), Value::Str(right)) => Ok(Value::Bool(left >= right)),
+                _ => Err(self.number_operands_error(operator)),
+            },
 
-                    TokenType::Greater => match (left, right) {
-                        (Value::Number(left), Value::Number(right)) => {
-                            Ok(Value::Bool(left > right))
-                        }
-                        _ => Err(self.number_operands_error(operator)),
-                    },
+            TokenType::Less => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Bool(left < right)),
+                (Value::Str(left), Value::Str(right)) => Ok(Value::Bool(left < right)),
+                _ => Err(self.number_operands_error(operator)),
+            },
 
-                    TokenType::GreaterEqual => match (left, right) {
-                        (Value::Number(left), Value::Number(right)) => {
-                            Ok(Value::Bool(left >= right))
-                        }
-                        _ => Err(self.number_operands_error(operator)),
-                    },
+            TokenType::LessEqual => match (left, right) {
+                (Value::Number(left), Value::Number(right)) => Ok(Value::Bool(left <= right)),
+                (Value::Str(left), Value::Str(right)) => Ok(Value::Bool(left <= right)),
+                _ => Err(self.number_operands_error(operator)),
+            },
 
-                    TokenType::Less => match (left, right) {
-                        (Value::Number(left), Value::Number(right)) => {
-                            Ok(Value::Bool(left < right))
-                        }
-                        _ => Err(self.number_operands_error(operator)),
-                    },
+            TokenType::BangEqual => Ok(Value::Bool(!self.is_equal(left, right))),
 
-                    TokenType::LessEqual => match (left, right) {
-                        (Value::Number(left), Value::Number(right)) => {
-                            Ok(Value::Bool(left <= right))
-                        }
-                        _ => Err(self.number_operands_error(operator)),
-                    },
+            TokenType::EqualEqual => Ok(Value::Bool(self.is_equal(left, right))),
 
-                    TokenType::BangEqual => Ok(Value::Bool(!self.is_equal(left, right))),
+            TokenType::Comma => Ok(right),
 
-                    TokenType::EqualEqual => Ok(Value::Bool(self.is_equal(left, right))),
+            _ => Ok(Value::Nil),
+        }
+    }
 
-                    _ => Ok(Value::Nil),
-                }
+    fn assign_variable(
+        &mut self,
+        id: ExprId,
+        name: &Token,
+        value: Value,
+    ) -> Result<(), RuntimeError> {
+        match self.locals.get(&id) {
+            Some(&distance) => self
+                .environment
+                .borrow_mut()
+                .assign_at(distance, name, value),
+            None => self.globals.borrow_mut().assign(name, value),
+        }
+    }
+
+    /// Suma o resta uno al operando de `++`/`--` y devuelve los valores anterior y nuevo.
+    fn increment(&mut self, operand: &Expr, operator: &Token) -> Result<(f64, f64), RuntimeError> {
+        let delta = if operator.kind == TokenType::PlusPlus {
+            1.0
+        } else {
+            -1.0
+        };
+        let bump = |value: Value| match value {
+            Value::Number(n) => Ok((n, n + delta)),
+            _ => Err(RuntimeError::new(
+                operator.clone(),
+                "Operand must be a number.",
+            )),
+        };
+
+        match operand {
+            Expr::Variable { id, name } => {
+                let (old, new) = bump(self.look_up_variable(*id, name)?)?;
+                self.assign_variable(*id, name, Value::Number(new))?;
+                Ok((old, new))
+            }
+            Expr::Get { object, name } => {
+                let Value::Instance(instance) = self.evaluate(object)? else {
+                    return Err(RuntimeError::new(
+                        name.clone(),
+                        "Only instances have fields.",
+                    ));
+                };
+                let (old, new) = bump(LoxInstance::get(&instance, name)?)?;
+                instance.borrow_mut().set(name, Value::Number(new));
+                Ok((old, new))
+            }
+            Expr::Index {
+                object,
+                bracket,
+                index,
+            } => {
+                let object = self.evaluate(object)?;
+                let index = self.evaluate(index)?;
+                let (old, new) = bump(self.get_index(&object, &index, bracket)?)?;
+                self.set_index(&object, index, Value::Number(new), bracket)?;
+                Ok((old, new))
             }
+            _ => unreachable!("el parser solo acepta variables, propiedades e índices"),
+        }
+    }
+
+    fn look_up_variable(&mut self, id: ExprId, name: &Token) -> Result<Value, RuntimeError> {
+        match self.locals.get(&id) {
+            Some(&distance) => self.environment.borrow().get_at(distance, name),
+            None => self.globals.borrow().get(name),
+        }
+    }
+
+    fn integer_operands(
+        &mut self,
+        operator: &Token,
+        left: Value,
+        right: Value,
+    ) -> Result<(i64, i64), RuntimeError> {
+        // Fuera del rango de `i64` la conversión con `as` saturaría en silencio.
+        let is_integer =
+            |n: f64| n.fract() == 0.0 && (i64::MIN as f64..i64::MAX as f64).contains(&n);
+        match (left, right) {
+            (Value::Number(left), Value::Number(right))
+                if is_integer(left) && is_integer(right) =>
+            {
+                Ok((left as i64, right as i64))
+            }
+            _ => Err(RuntimeError::new(
+                operator.clone(),
+                "Bitwise operands must be integers.",
+            )),
         }
     }
 
@@ -186,11 +1061,43 @@ impl Interpreter {
         RuntimeError::new(operator.clone(), "Operand must be a number.")
     }
 
+    /// Llama a `function` tras comprobar la cantidad de argumentos y la profundidad
+    /// de la pila. Los errores se atribuyen a `paren`.
+    pub(crate) fn call_function(
+        &mut self,
+        function: &dyn LoxCallable,
+        paren: &Token,
+        arguments: Vec<Value>,
+    ) -> Result<Value, RuntimeError> {
+        let (arity, max_arity) = (function.arity(), function.max_arity());
+        if arguments.len() < arity || arguments.len() > max_arity {
+            let expected = if arity == max_arity {
+                arity.to_string()
+            } else if max_arity == usize::MAX {
+                format!("at least {arity}")
+            } else {
+                format!("{arity} to {max_arity}")
+            };
+            let message = format!("Expected {expected} arguments but got {}.", arguments.len());
+            return Err(RuntimeError::new(paren.clone(), &message));
+        }
+
+        // Evita que una recursión sin fin desborde la pila de Rust.
+        if self.call_depth >= self.max_call_depth {
+            return Err(RuntimeError::new(paren.clone(), "Stack overflow."));
+        }
+        self.call_depth += 1;
+        let result = function.call(self, paren, arguments);
+        self.call_depth -= 1;
+        result
+    }
+
     fn number_operands_error(&mut self, operator: &Token) -> RuntimeError {
-        RuntimeError::new(operator.clone(), "Operands must be a number.")
+        RuntimeError::new(operator.clone(), "Operands must be numbers.")
     }
 
-    fn is_truthy(&mut self, val: Value) -> bool {
+    /// Veracidad canónica de Lox: solo `nil` y `false` son falsos; `0` y `""` son verdaderos.
+    pub(crate) fn is_truthy(&mut self, val: Value) -> bool {
         match val {
             Value::Nil => false,
             Value::Bool(b) => b,
@@ -198,27 +1105,165 @@ impl Interpreter {
         }
     }
 
+    /// `object[index]`: posición de una lista, carácter de una cadena o clave de un
+    /// mapa (`nil` si no existe).
+    fn get_index(
+        &self,
+        object: &Value,
+        index: &Value,
+        bracket: &Token,
+    ) -> Result<Value, RuntimeError> {
+        match object {
+            Value::List(list) => {
+                let list = list.borrow();
+                let index = self.position(index, list.len(), bracket, "List")?;
+                Ok(list[index].clone())
+            }
+            Value::Str(s) => {
+                let index = self.position(index, s.chars().count(), bracket, "String")?;
+                Ok(Value::Str(s.chars().nth(index).unwrap().to_string().into()))
+            }
+            Value::Map(map) => {
+                let key = self.map_key(index.clone(), bracket)?;
+                Ok(map.borrow().get(&key).cloned().unwrap_or(Value::Nil))
+            }
+            _ => Err(RuntimeError::new(
+                bracket.clone(),
+                "Only lists, maps and strings can be indexed.",
+            )),
+        }
+    }
+
+    /// `object[start..end]`: nueva lista o cadena con los elementos de ese rango.
+    fn slice(
+        &self,
+        object: &Value,
+        start: &Value,
+        end: &Value,
+        bracket: &Token,
+    ) -> Result<Value, RuntimeError> {
+        let (len, kind) = match object {
+            Value::List(list) => (list.borrow().len(), "List"),
+            Value::Str(s) => (s.chars().count(), "String"),
+            _ => {
+                return Err(RuntimeError::new(
+                    bracket.clone(),
+                    "Only lists and strings can be sliced.",
+                ));
+            }
+        };
+        // Los extremos pueden valer `len`: `s[0..len(s)]` es la cadena entera.
+        let start = self.position(start, len + 1, bracket, kind)?;
+        let end = self.position(end, len + 1, bracket, kind)?;
+        if start > end {
+            return Err(RuntimeError::new(
+                bracket.clone(),
+                "Slice start must not be greater than its end.",
+            ));
+        }
+
+        match object {
+            Value::List(list) => {
+                let elements = list.borrow()[start..end].to_vec();
+                Ok(Value::List(Rc::new(RefCell::new(elements))))
+            }
+            Value::Str(s) => Ok(Value::Str(
+                s.chars()
+                    .skip(start)
+                    .take(end - start)
+                    .collect::<String>()
+                    .into(),
+            )),
+            _ => unreachable!(),
+        }
+    }
+
+    fn set_index(
+        &self,
+        object: &Value,
+        index: Value,
+        value: Value,
+        bracket: &Token,
+    ) -> Result<(), RuntimeError> {
+        match object {
+            Value::List(list) => {
+                let index = self.position(&index, list.borrow().len(), bracket, "List")?;
+                list.borrow_mut()[index] = value;
+                Ok(())
+            }
+            Value::Map(map) => {
+                let key = self.map_key(index, bracket)?;
+                map.borrow_mut().insert(key, value);
+                Ok(())
+            }
+            Value::Str(_) => Err(RuntimeError::new(bracket.clone(), "Strings are immutable.")),
+            _ => Err(RuntimeError::new(
+                bracket.clone(),
+                "Only lists and maps can be indexed.",
+            )),
+        }
+    }
+
+    fn map_key(&self, key: Value, token: &Token) -> Result<String, RuntimeError> {
+        match key {
+            Value::Str(key) => Ok(key.to_string()),
+            _ => Err(RuntimeError::new(
+                token.clone(),
+                "Map keys must be strings.",
+            )),
+        }
+    }
+
+    /// Convierte `index` en una posición de `0..len`. `kind` nombra el objeto
+    /// indexado en los mensajes de error.
+    fn position(
+        &self,
+        index: &Value,
+        len: usize,
+        bracket: &Token,
+        kind: &str,
+    ) -> Result<usize, RuntimeError> {
+        let error =
+            |message: &str| RuntimeError::new(bracket.clone(), &format!("{kind} {message}"));
+        let &Value::Number(index) = index else {
+            return Err(error("index must be a number."));
+        };
+        if index.fract() != 0.0 {
+            return Err(error("index must be an integer."));
+        }
+        if index < 0.0 || index >= len as f64 {
+            return Err(error("index out of range."));
+        }
+        Ok(index as usize)
+    }
+
     fn is_equal(&mut self, left: Value, right: Value) -> bool {
         match (left, right) {
             (Value::Bool(l), Value::Bool(r)) => l == r,
+            // Igualdad IEEE 754: NaN no es igual a nada, ni a sí mismo.
+            (Value::Number(l), Value::Number(r)) => l == r,
+            (Value::Str(l), Value::Str(r)) => l == r,
             (Value::Nil, Value::Nil) => true,
+            (Value::Callable(l), Value::Callable(r)) => Rc::ptr_eq(&l, &r),
+            (Value::Class(l), Value::Class(r)) => Rc::ptr_eq(&l, &r),
+            (Value::Instance(l), Value::Instance(r)) => Rc::ptr_eq(&l, &r),
+            (Value::List(l), Value::List(r)) => Rc::ptr_eq(&l, &r),
+            (Value::Map(l), Value::Map(r)) => Rc::ptr_eq(&l, &r),
             _ => false,
         }
     }
+}
 
-    fn stringify(&mut self, value: Value) -> String {
-        match value {
-            Value::Nil => "nil".into(),
-            Value::Str(str) => str,
-            Value::Bool(b) => b.to_string(),
-            Value::Number(number) => {
-                let text = number.to_string();
-                if text.ends_with(".0") {
-                    text.trim_end_matches(".0").to_string()
-                } else {
-                    text
-                }
-            }
-        }
+/// Método que sobrecarga un operador binario cuando el operando izquierdo es una
+/// instancia. `!=` usa `__eq__` y niega el resultado.
+fn operator_method(operator: TokenType) -> Option<&'static str> {
+    match operator {
+        TokenType::Plus => Some("__add__"),
+        TokenType::Minus => Some("__sub__"),
+        TokenType::Star => Some("__mul__"),
+        TokenType::Slash => Some("__div__"),
+        TokenType::EqualEqual | TokenType::BangEqual => Some("__eq__"),
+        TokenType::Less => Some("__lt__"),
+        _ => None,
     }
 }
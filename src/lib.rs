@@ -0,0 +1,44 @@
+pub mod ast_json;
+pub mod ast_printer;
+pub mod callable;
+pub mod class;
+pub mod environment;
+pub mod interpreter;
+pub mod lox;
+pub mod natives;
+pub mod optimizer;
+pub mod parser;
+pub mod resolver;
+pub mod scanner;
+pub mod token;
+
+use std::{io, thread};
+
+pub use lox::{Diagnostic, Lox};
+
+/// Pila necesaria para llegar a `max_call_depth` llamadas anidadas: cada llamada de
+/// Lox usa varios marcos de la pila de Rust. Quien use `Lox` directamente debe
+/// ejecutarlo en un hilo con esta pila.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Ejecuta un programa Lox y, si falló, devuelve los diagnósticos producidos
+/// (avisos incluidos). Corre en un hilo propio con `STACK_SIZE` de pila.
+pub fn run_source(source: &str) -> Result<(), Vec<Diagnostic>> {
+    thread::scope(|scope| {
+        let run = thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, || {
+                // Los diagnósticos se devuelven; no se escriben en la salida de errores.
+                let mut lox = Lox::with_output(Box::new(io::stdout()), Box::new(io::sink()));
+                lox.run(source);
+                if !lox.had_error && !lox.had_runtime_error {
+                    Ok(())
+                } else {
+                    Err(lox.diagnostics)
+                }
+            })
+            .unwrap();
+        run.join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
@@ -1,50 +1,180 @@
-use std::io::{self, BufRead, Write};
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    interpreter::{Interpreter, RuntimeError},
+    ast_json, ast_printer,
+    interpreter::{Interpreter, RuntimeError, Stmt},
+    optimizer,
     parser::Parser,
+    resolver::Resolver,
     scanner::Scanner,
     token::{Token, TokenType},
 };
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub line: usize,
+    pub column: usize,
+    /// Cantidad de caracteres a subrayar a partir de `column`.
+    pub len: usize,
+    pub location: String,
+    pub message: String,
+    /// Los avisos se informan, pero no impiden ejecutar el programa.
+    pub warning: bool,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.warning { "Warning" } else { "Error" };
+        write!(
+            f,
+            "[line {}, col {}] {kind}{}: {}",
+            self.line, self.column, self.location, self.message
+        )
+    }
+}
+
 pub struct Lox {
     pub had_error: bool,
     pub had_runtime_error: bool,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Aplica el plegado de constantes antes de ejecutar.
+    pub optimize: bool,
+    /// Muestra la línea de código de cada error, subrayando el token con `^`.
+    pub underline_errors: bool,
+    /// Detiene el programa en el primer error de ejecución en lugar de seguir
+    /// con la siguiente sentencia.
+    pub stop_on_error: bool,
+    /// Al terminar `run`, escribe en la salida de errores cuánto tardó cada fase.
+    pub time: bool,
+    timings: Vec<(&'static str, Duration)>,
+    source: String,
+    reported: usize,
+    interpreter: Interpreter,
+    err: Box<dyn Write>,
+}
+
+impl Default for Lox {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Lox {
     pub fn new() -> Self {
+        Self::with_output(Box::new(io::stdout()), Box::new(io::stderr()))
+    }
+
+    /// Crea un `Lox` que escribe la salida del programa en `out` y los errores en `err`.
+    pub fn with_output(out: Box<dyn Write>, err: Box<dyn Write>) -> Self {
         Self {
             had_error: false,
             had_runtime_error: false,
+            diagnostics: Vec::new(),
+            optimize: true,
+            underline_errors: false,
+            stop_on_error: false,
+            time: false,
+            timings: Vec::new(),
+            source: String::new(),
+            reported: 0,
+            interpreter: Interpreter::with_output(out),
+            err,
         }
     }
 
+    /// Ejecuta un programa completo. Los errores quedan en `had_error`,
+    /// `had_runtime_error` y `diagnostics`; el código de salida lo decide `run_file`.
     pub fn run(&mut self, source: &str) {
+        self.run_with(source, false);
+        if self.time {
+            for (phase, elapsed) in &self.timings {
+                let ms = elapsed.as_secs_f64() * 1000.0;
+                writeln!(self.err, "[time] {phase}: {ms:.3} ms").unwrap();
+            }
+        }
+    }
+
+    /// Ejecuta una línea del REPL; los errores se informan pero no terminan la sesión.
+    pub fn run_line(&mut self, line: &str) {
+        self.run_with(line, true);
+        self.had_error = false;
+        self.had_runtime_error = false;
+    }
+
+    /// Escanea y parsea el código sin ejecutarlo.
+    pub fn parse(&mut self, source: &str) -> Vec<Stmt> {
+        self.parse_with(source, false)
+    }
+
+    fn parse_with(&mut self, source: &str, repl: bool) -> Vec<Stmt> {
+        self.source = source.to_string();
+        self.timings.clear();
+
+        let start = Instant::now();
         let mut scanner = Scanner::new(source, self);
-        let mut interpreter = Interpreter::new();
         let tokens = scanner.scan_tokens();
-        let mut parser = Parser::new(tokens.clone(), self);
-        let statements = parser.parse().unwrap();
+        self.timings.push(("scan", start.elapsed()));
 
-        if self.had_error {
-            return;
+        let start = Instant::now();
+        let mut parser = Parser::new(tokens, self);
+        let statements = if repl {
+            parser.parse_repl()
+        } else {
+            parser.parse()
         };
+        self.timings.push(("parse", start.elapsed()));
+        self.flush_diagnostics();
+        statements
+    }
 
-        println!("{:?}", interpreter.interpret(self, statements));
+    fn run_with(&mut self, source: &str, repl: bool) {
+        let mut statements = self.parse_with(source, repl);
 
+        // Los errores de escaneo o parseo impiden resolver y ejecutar.
         if self.had_error {
-            self.clear_error();
-            std::process::exit(65);
+            return;
         }
 
-        if self.had_runtime_error {
-            self.clear_error();
+        if self.optimize {
+            optimizer::fold_constants(&mut statements);
         }
+
+        // El intérprete vive en `Lox` para conservar el estado entre líneas del REPL.
+        let mut interpreter = std::mem::take(&mut self.interpreter);
+        let start = Instant::now();
+        Resolver::new(&mut interpreter, self).resolve(&statements);
+        self.timings.push(("resolve", start.elapsed()));
+        self.flush_diagnostics();
+        if !self.had_error {
+            let start = Instant::now();
+            if repl {
+                interpreter.interpret_repl(self, statements);
+            } else {
+                interpreter.interpret(self, statements);
+            }
+            self.timings.push(("interpret", start.elapsed()));
+        }
+        self.interpreter = interpreter;
+    }
+
+    /// Directorio desde el que se resuelven las rutas relativas de `import`; por
+    /// defecto, el directorio actual.
+    pub fn set_script_dir(&mut self, dir: &Path) {
+        self.interpreter.script_dir = dir.to_path_buf();
     }
 
     pub fn run_file(&mut self, path: &str) -> std::io::Result<()> {
-        let source = std::fs::read_to_string(path).unwrap();
+        let source = Self::read_source(path);
+        if path != "-"
+            && let Some(dir) = Path::new(path).parent()
+        {
+            self.set_script_dir(dir);
+        }
         self.run(&source);
 
         if self.had_error {
@@ -56,17 +186,102 @@ impl Lox {
         Ok(())
     }
 
-    pub fn run_prompt(&mut self) {
-        let stdin = io::stdin();
-        let mut lines = stdin.lock().lines();
+    /// Valida el programa (escaneo, parseo y resolución) sin ejecutarlo.
+    pub fn check_file(&mut self, path: &str) -> std::io::Result<()> {
+        let source = Self::read_source(path);
+        let statements = self.parse(&source);
+
+        if !self.had_error {
+            Resolver::new(&mut Interpreter::new(), self).resolve(&statements);
+            self.flush_diagnostics();
+        }
+        if self.had_error {
+            std::process::exit(65)
+        }
+        Ok(())
+    }
+
+    pub fn print_ast(&mut self, path: &str) -> std::io::Result<()> {
+        let source = Self::read_source(path);
+        let statements = self.parse(&source);
 
+        if self.had_error {
+            std::process::exit(65)
+        }
+        for statement in &statements {
+            println!("{}", ast_printer::print_stmt(statement));
+        }
+        Ok(())
+    }
+
+    pub fn print_ast_json(&mut self, path: &str) -> std::io::Result<()> {
+        let source = Self::read_source(path);
+        let statements = self.parse(&source);
+
+        if self.had_error {
+            std::process::exit(65)
+        }
+        println!("{}", ast_json::program_to_json(&statements));
+        Ok(())
+    }
+
+    pub fn print_tokens(&mut self, path: &str) -> std::io::Result<()> {
+        let source = Self::read_source(path);
+        self.source = source.clone();
+        let tokens = Scanner::new(source, self).scan_tokens();
+        self.flush_diagnostics();
+
+        for token in &tokens {
+            println!("{token}");
+        }
+        if self.had_error {
+            std::process::exit(65)
+        }
+        Ok(())
+    }
+
+    /// Lee el programa de `path`, o de la entrada estándar si `path` es `-`.
+    /// Si no se puede leer, termina con el código 66 (EX_NOINPUT).
+    fn read_source(path: &str) -> String {
+        let result = if path == "-" {
+            let mut source = String::new();
+            io::stdin().read_to_string(&mut source).map(|_| source)
+        } else {
+            std::fs::read_to_string(path)
+        };
+
+        result.unwrap_or_else(|error| {
+            let reason = match error.kind() {
+                io::ErrorKind::NotFound => "file not found".to_string(),
+                io::ErrorKind::PermissionDenied => "permission denied".to_string(),
+                _ => error.to_string(),
+            };
+            eprintln!("Error: could not read '{path}': {reason}");
+            std::process::exit(66)
+        })
+    }
+
+    pub fn run_prompt(&mut self) {
+        let mut buffer = String::new();
+        // No se retiene el bloqueo de stdin entre líneas: `input()` también lo usa.
         loop {
-            print!("> ");
+            print!("{}", if buffer.is_empty() { "> " } else { "... " });
             io::stdout().flush().unwrap();
-            match lines.next() {
-                None => break,
-                Some(Ok(line)) => self.run(&line),
-                Some(Err(e)) => {
+            let mut line = String::new();
+            match io::stdin().read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => {
+                    let line = line.trim_end_matches(['\n', '\r']);
+                    buffer.push_str(line);
+                    buffer.push('\n');
+                    // Una línea en blanco ejecuta lo acumulado aunque esté incompleto.
+                    if !line.trim().is_empty() && Self::is_incomplete(&buffer) {
+                        continue;
+                    }
+                    self.run_line(&buffer);
+                    buffer.clear();
+                }
+                Err(e) => {
                     eprintln!("{e}");
                     break;
                 }
@@ -74,26 +289,87 @@ impl Lox {
         }
     }
 
-    pub fn error(&mut self, line: usize, message: &str) {
-        self.report(line, "", message);
+    /// Indica si `source` solo falla porque termina antes de tiempo (por ejemplo, un
+    /// bloque sin cerrar), de modo que el REPL debe seguir leyendo líneas.
+    fn is_incomplete(source: &str) -> bool {
+        let mut scratch = Lox::with_output(Box::new(io::sink()), Box::new(io::sink()));
+        scratch.parse_with(source, true);
+        scratch.had_error
+            && scratch.diagnostics.iter().all(|diagnostic| {
+                diagnostic.location == " at end" || diagnostic.message.starts_with("Unterminated")
+            })
+    }
+
+    pub fn error(&mut self, line: usize, column: usize, message: &str) {
+        self.report(line, column, 1, "", message);
     }
 
     pub fn error_at(&mut self, token: &Token, message: &str) {
+        let len = token.len.max(1);
         if token.kind == TokenType::Eof {
-            self.report(token.line, " at end", message);
+            self.report(token.line, token.column, len, " at end", message);
         } else {
-            let _where = format!(" at '{}'", &token.lexeme);
-            self.report(token.line, _where.as_str(), message);
+            let _where = format!(" at '{}'", token.lexeme);
+            self.report(token.line, token.column, len, _where.as_str(), message);
         }
     }
 
-    fn report(&mut self, line: usize, where_: &str, message: &str) {
-        eprintln!("[line {line}] Error{where_}: {message}");
+    /// Como `error_at`, pero no marca `had_error`: el programa se ejecuta igual.
+    pub fn warning_at(&mut self, token: &Token, message: &str) {
+        self.diagnostics.push(Diagnostic {
+            line: token.line,
+            column: token.column,
+            len: token.len.max(1),
+            location: format!(" at '{}'", token.lexeme),
+            message: message.to_string(),
+            warning: true,
+        });
+    }
+
+    fn report(&mut self, line: usize, column: usize, len: usize, where_: &str, message: &str) {
+        self.diagnostics.push(Diagnostic {
+            line,
+            column,
+            len,
+            location: where_.to_string(),
+            message: message.to_string(),
+            warning: false,
+        });
         self.had_error = true;
     }
 
+    /// Imprime, ordenados por posición, los diagnósticos aún no mostrados.
+    fn flush_diagnostics(&mut self) {
+        let pending = &mut self.diagnostics[self.reported..];
+        pending.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+        for diagnostic in pending.iter() {
+            writeln!(self.err, "{diagnostic}").unwrap();
+            if self.underline_errors
+                && let Some(text) = source_lines(&self.source).nth(diagnostic.line - 1)
+            {
+                writeln!(self.err, "{text}").unwrap();
+                writeln!(self.err, "{}", underline(text, diagnostic)).unwrap();
+            }
+        }
+        self.reported = self.diagnostics.len();
+    }
+
     pub fn runtime_error(&mut self, error: RuntimeError) {
-        eprintln!("{} \n[line {} ]", error.message, error.token.line);
+        writeln!(
+            self.err,
+            "[line {}] Runtime error: {}",
+            error.token.line, error.message
+        )
+        .unwrap();
+        self.diagnostics.push(Diagnostic {
+            line: error.token.line,
+            column: error.token.column,
+            len: error.token.len,
+            location: String::new(),
+            message: error.message,
+            warning: false,
+        });
+        self.reported = self.diagnostics.len();
         self.had_runtime_error = true;
     }
 
@@ -101,3 +377,23 @@ impl Lox {
         self.had_error = false;
     }
 }
+
+/// Líneas de `source` separadas igual que en el scanner: `\n`, `\r\n` o un `\r` suelto.
+fn source_lines(source: &str) -> impl Iterator<Item = &str> {
+    source.split('\n').flat_map(|line| {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        line.split('\r')
+    })
+}
+
+/// Línea de `^` bajo el rango del diagnóstico. Los tabuladores previos se copian
+/// para que el subrayado quede alineado con el texto.
+fn underline(text: &str, diagnostic: &Diagnostic) -> String {
+    let mut out: String = text
+        .chars()
+        .take(diagnostic.column - 1)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    out.push_str(&"^".repeat(diagnostic.len.max(1)));
+    out
+}
@@ -1,20 +1,34 @@
-#![feature(box_into_inner)]
-mod interpreter;
-mod lox;
-mod parser;
-mod scanner;
-mod token;
-
-use lox::Lox;
+use jlox::{Lox, STACK_SIZE};
 
 fn main() {
+    let interpreter = std::thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(run)
+        .unwrap();
+    if interpreter.join().is_err() {
+        std::process::exit(70);
+    }
+}
+
+fn run() {
     let args: Vec<_> = std::env::args().skip(1).collect();
     let mut lox = Lox::new();
+    lox.underline_errors = true;
     match args.as_slice() {
         [] => lox.run_prompt(),
+        [flag, path] if flag == "--ast" => lox.print_ast(path).unwrap(),
+        [flag, path] if flag == "--ast-json" => lox.print_ast_json(path).unwrap(),
+        [flag, path] if flag == "--check" => lox.check_file(path).unwrap(),
+        [flag, path] if flag == "--tokens" => lox.print_tokens(path).unwrap(),
+        [flag, path] if flag == "--time" => {
+            lox.time = true;
+            lox.run_file(path).unwrap()
+        }
         [path] => lox.run_file(path).unwrap(),
         _ => {
-            eprintln!("Usage: jlox [script]");
+            eprintln!(
+                "Usage: jlox [--ast | --ast-json | --check | --time | --tokens] [script | -]"
+            );
             std::process::exit(64)
         }
     }
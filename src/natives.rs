@@ -0,0 +1,404 @@
+use std::{
+    cell::RefCell,
+    io,
+    rc::Rc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    callable::{LoxCallable, NativeFunction},
+    environment::Environment,
+    interpreter::{Interpreter, RuntimeError, Value},
+    token::Token,
+};
+
+pub fn define_globals(globals: &mut Environment) {
+    let natives = [
+        NativeFunction {
+            name: "abs",
+            arity: 1,
+            max_arity: 1,
+            function: abs,
+        },
+        NativeFunction {
+            name: "assert",
+            arity: 2,
+            max_arity: 2,
+            function: assert,
+        },
+        NativeFunction {
+            name: "ceil",
+            arity: 1,
+            max_arity: 1,
+            function: ceil,
+        },
+        NativeFunction {
+            name: "chr",
+            arity: 1,
+            max_arity: 1,
+            function: chr,
+        },
+        NativeFunction {
+            name: "clock",
+            arity: 0,
+            max_arity: 0,
+            function: clock,
+        },
+        NativeFunction {
+            name: "filter",
+            arity: 2,
+            max_arity: 2,
+            function: filter,
+        },
+        NativeFunction {
+            name: "floor",
+            arity: 1,
+            max_arity: 1,
+            function: floor,
+        },
+        NativeFunction {
+            name: "input",
+            arity: 0,
+            max_arity: 1,
+            function: input,
+        },
+        NativeFunction {
+            name: "len",
+            arity: 1,
+            max_arity: 1,
+            function: len,
+        },
+        NativeFunction {
+            name: "map",
+            arity: 2,
+            max_arity: 2,
+            function: map,
+        },
+        NativeFunction {
+            name: "num",
+            arity: 1,
+            max_arity: 1,
+            function: num,
+        },
+        NativeFunction {
+            name: "ord",
+            arity: 1,
+            max_arity: 1,
+            function: ord,
+        },
+        NativeFunction {
+            name: "pop",
+            arity: 1,
+            max_arity: 1,
+            function: pop,
+        },
+        NativeFunction {
+            name: "push",
+            arity: 2,
+            max_arity: 2,
+            function: push,
+        },
+        NativeFunction {
+            name: "reduce",
+            arity: 3,
+            max_arity: 3,
+            function: reduce,
+        },
+        NativeFunction {
+            name: "round",
+            arity: 1,
+            max_arity: 1,
+            function: round,
+        },
+        NativeFunction {
+            name: "sleep",
+            arity: 1,
+            max_arity: 1,
+            function: sleep,
+        },
+        NativeFunction {
+            name: "sqrt",
+            arity: 1,
+            max_arity: 1,
+            function: sqrt,
+        },
+        NativeFunction {
+            name: "str",
+            arity: 1,
+            max_arity: 1,
+            function: str,
+        },
+        NativeFunction {
+            name: "type",
+            arity: 1,
+            max_arity: 1,
+            function: type_of,
+        },
+    ];
+
+    for native in natives {
+        globals.define(native.name, Value::Callable(Rc::new(native)));
+    }
+}
+
+fn clock(_: &mut Interpreter, _: &Token, _: Vec<Value>) -> Result<Value, RuntimeError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Ok(Value::Number(now.as_secs_f64()))
+}
+
+fn len(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    match &arguments[0] {
+        Value::Str(s) => Ok(Value::Number(s.chars().count() as f64)),
+        Value::List(list) => Ok(Value::Number(list.borrow().len() as f64)),
+        Value::Map(map) => Ok(Value::Number(map.borrow().len() as f64)),
+        _ => Err(RuntimeError::new(paren.clone(), "Object has no length.")),
+    }
+}
+
+fn num(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    match &arguments[0] {
+        Value::Number(n) => Ok(Value::Number(*n)),
+        Value::Str(s) => s
+            .trim()
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| RuntimeError::new(paren.clone(), "Cannot convert to number.")),
+        _ => Err(RuntimeError::new(
+            paren.clone(),
+            "Cannot convert to number.",
+        )),
+    }
+}
+
+fn abs(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    math(paren, &arguments[0], f64::abs)
+}
+
+fn ceil(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    math(paren, &arguments[0], f64::ceil)
+}
+
+fn floor(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    math(paren, &arguments[0], f64::floor)
+}
+
+fn round(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    math(paren, &arguments[0], f64::round)
+}
+
+/// `sqrt` de un negativo da NaN, igual que `f64::sqrt`.
+fn sqrt(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    math(paren, &arguments[0], f64::sqrt)
+}
+
+fn math(paren: &Token, argument: &Value, operation: fn(f64) -> f64) -> Result<Value, RuntimeError> {
+    match argument {
+        Value::Number(n) => Ok(Value::Number(operation(*n))),
+        _ => Err(RuntimeError::new(
+            paren.clone(),
+            "Argument must be a number.",
+        )),
+    }
+}
+
+/// Cadena de un carácter con el valor escalar Unicode `n`.
+fn chr(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    let c = match arguments[0] {
+        Value::Number(n) if n.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&n) => {
+            char::from_u32(n as u32)
+        }
+        _ => None,
+    };
+    c.map(|c| Value::Str(c.to_string().into())).ok_or_else(|| {
+        RuntimeError::new(paren.clone(), "chr() expects a valid Unicode scalar value.")
+    })
+}
+
+/// Valor escalar Unicode de una cadena de exactamente un carácter.
+fn ord(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    if let Value::Str(s) = &arguments[0] {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Value::Number(u32::from(c) as f64));
+        }
+    }
+    Err(RuntimeError::new(
+        paren.clone(),
+        "ord() expects a single-character string.",
+    ))
+}
+
+fn str(_: &mut Interpreter, _: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    Ok(Value::Str(arguments[0].to_string().into()))
+}
+
+fn type_of(_: &mut Interpreter, _: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    let name = match &arguments[0] {
+        Value::Nil => "nil",
+        Value::Bool(_) => "bool",
+        Value::Number(_) => "number",
+        Value::Str(_) => "string",
+        Value::Callable(_) => "function",
+        Value::Class(_) => "class",
+        Value::Instance(_) => "instance",
+        Value::List(_) => "list",
+        Value::Map(_) => "map",
+    };
+    Ok(Value::Str(name.into()))
+}
+
+/// Lee una línea de la entrada estándar, sin el salto final; `nil` al llegar al final.
+fn input(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    arguments: Vec<Value>,
+) -> Result<Value, RuntimeError> {
+    if let Some(prompt) = arguments.first() {
+        let out = interpreter.output();
+        write!(out, "{prompt}").unwrap();
+        out.flush().unwrap();
+    }
+
+    let mut line = String::new();
+    match io::stdin().read_line(&mut line) {
+        Ok(0) => Ok(Value::Nil),
+        Ok(_) => {
+            if line.ends_with('\n') {
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+            }
+            Ok(Value::Str(line.into()))
+        }
+        Err(error) => Err(RuntimeError::new(paren.clone(), &error.to_string())),
+    }
+}
+
+/// Detiene la ejecución (de forma síncrona) durante los milisegundos indicados.
+fn sleep(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    // `try_from_secs_f64` rechaza negativos, NaN, infinitos y valores demasiado grandes.
+    let duration = match arguments[0] {
+        Value::Number(ms) => Duration::try_from_secs_f64(ms / 1000.0).ok(),
+        _ => None,
+    };
+    let Some(duration) = duration else {
+        return Err(RuntimeError::new(
+            paren.clone(),
+            "sleep() expects a non-negative number of milliseconds.",
+        ));
+    };
+    thread::sleep(duration);
+    Ok(Value::Nil)
+}
+
+fn assert(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    arguments: Vec<Value>,
+) -> Result<Value, RuntimeError> {
+    let mut arguments = arguments.into_iter();
+    if interpreter.is_truthy(arguments.next().unwrap()) {
+        return Ok(Value::Nil);
+    }
+    let message = arguments.next().unwrap().to_string();
+    Err(RuntimeError::new(paren.clone(), &message))
+}
+
+/// Nueva lista con el resultado de aplicar la función a cada elemento.
+fn map(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    arguments: Vec<Value>,
+) -> Result<Value, RuntimeError> {
+    let (elements, function) = list_and_function("map", paren, &arguments, 1)?;
+    let mut result = Vec::with_capacity(elements.len());
+    for element in elements {
+        result.push(interpreter.call_function(function.as_ref(), paren, vec![element])?);
+    }
+    Ok(Value::List(Rc::new(RefCell::new(result))))
+}
+
+/// Nueva lista con los elementos para los que la función devuelve un valor verdadero.
+fn filter(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    arguments: Vec<Value>,
+) -> Result<Value, RuntimeError> {
+    let (elements, function) = list_and_function("filter", paren, &arguments, 1)?;
+    let mut result = Vec::new();
+    for element in elements {
+        let keep = interpreter.call_function(function.as_ref(), paren, vec![element.clone()])?;
+        if interpreter.is_truthy(keep) {
+            result.push(element);
+        }
+    }
+    Ok(Value::List(Rc::new(RefCell::new(result))))
+}
+
+/// Pliega la lista de izquierda a derecha: `fn(fn(init, a), b)`...
+fn reduce(
+    interpreter: &mut Interpreter,
+    paren: &Token,
+    arguments: Vec<Value>,
+) -> Result<Value, RuntimeError> {
+    let (elements, function) = list_and_function("reduce", paren, &arguments, 2)?;
+    let mut accumulator = arguments[2].clone();
+    for element in elements {
+        accumulator =
+            interpreter.call_function(function.as_ref(), paren, vec![accumulator, element])?;
+    }
+    Ok(accumulator)
+}
+
+/// Comprueba los argumentos de `map`, `filter` y `reduce`. Los elementos se copian para
+/// que la función pueda modificar la lista sin afectar al recorrido.
+fn list_and_function(
+    name: &str,
+    paren: &Token,
+    arguments: &[Value],
+    arity: usize,
+) -> Result<(Vec<Value>, Rc<dyn LoxCallable>), RuntimeError> {
+    let Value::List(list) = &arguments[0] else {
+        return Err(RuntimeError::new(
+            paren.clone(),
+            &format!("{name}() expects a list as first argument."),
+        ));
+    };
+    match &arguments[1] {
+        Value::Callable(function) if function.arity() <= arity && arity <= function.max_arity() => {
+            Ok((list.borrow().clone(), Rc::clone(function)))
+        }
+        _ => {
+            let plural = if arity == 1 { "" } else { "s" };
+            Err(RuntimeError::new(
+                paren.clone(),
+                &format!("{name}() expects a function taking {arity} argument{plural}."),
+            ))
+        }
+    }
+}
+
+/// Añade `value` al final de la lista (in situ) y devuelve la nueva longitud.
+fn push(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    let mut arguments = arguments.into_iter();
+    let Some(Value::List(list)) = arguments.next() else {
+        return Err(RuntimeError::new(paren.clone(), "push() expects a list."));
+    };
+    let mut list = list.borrow_mut();
+    list.push(arguments.next().unwrap());
+    Ok(Value::Number(list.len() as f64))
+}
+
+/// Quita y devuelve el último elemento de la lista.
+fn pop(_: &mut Interpreter, paren: &Token, arguments: Vec<Value>) -> Result<Value, RuntimeError> {
+    let Value::List(list) = &arguments[0] else {
+        return Err(RuntimeError::new(paren.clone(), "pop() expects a list."));
+    };
+    list.borrow_mut()
+        .pop()
+        .ok_or_else(|| RuntimeError::new(paren.clone(), "pop from empty list."))
+}
@@ -0,0 +1,256 @@
+use std::rc::Rc;
+
+use crate::{
+    interpreter::{Expr, Stmt, Value},
+    token::{Token, TokenType},
+};
+
+/// Pliega las expresiones cuyos operandos son literales (`1 + 2 * 3` pasa a ser `7`).
+/// Solo se pliegan operaciones que no pueden fallar: `1 / 0` o `"a" + 1` se dejan
+/// intactas para que el intérprete produzca el mismo resultado o error que antes.
+pub fn fold_constants(statements: &mut [Stmt]) {
+    for statement in statements {
+        fold_stmt(statement);
+    }
+}
+
+fn fold_stmt(stmt: &mut Stmt) {
+    match stmt {
+        Stmt::Break(_) | Stmt::Continue(_) | Stmt::Import { .. } => {}
+        Stmt::Expression(expr) | Stmt::Print { expr, .. } => fold_expr(expr),
+        Stmt::Function(declaration) => {
+            // Recién salida del parser, la declaración no está compartida.
+            if let Some(declaration) = Rc::get_mut(declaration) {
+                fold_constants(&mut declaration.body);
+            }
+        }
+        Stmt::Return { value, .. } => {
+            if let Some(value) = value {
+                fold_expr(value);
+            }
+        }
+        Stmt::Block(statements) => fold_constants(statements),
+        Stmt::Class {
+            superclass,
+            methods,
+            class_methods,
+            ..
+        } => {
+            if let Some(superclass) = superclass {
+                fold_expr(superclass);
+            }
+            for method in methods.iter_mut().chain(class_methods) {
+                if let Some(method) = Rc::get_mut(method) {
+                    fold_constants(&mut method.body);
+                }
+            }
+        }
+        Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            fold_expr(condition);
+            fold_stmt(then_branch);
+            if let Some(else_branch) = else_branch {
+                fold_stmt(else_branch);
+            }
+        }
+        Stmt::Switch {
+            subject,
+            cases,
+            default,
+        } => {
+            fold_expr(subject);
+            for (value, body) in cases {
+                fold_expr(value);
+                fold_stmt(body);
+            }
+            if let Some(default) = default {
+                fold_stmt(default);
+            }
+        }
+        Stmt::Var { initializer, .. } => {
+            if let Some(initializer) = initializer {
+                fold_expr(initializer);
+            }
+        }
+        Stmt::DoWhile { body, condition } => {
+            fold_stmt(body);
+            fold_expr(condition);
+        }
+        Stmt::While {
+            condition,
+            body,
+            increment,
+        } => {
+            fold_expr(condition);
+            fold_stmt(body);
+            if let Some(increment) = increment {
+                fold_expr(increment);
+            }
+        }
+    }
+}
+
+fn fold_expr(expr: &mut Expr) {
+    let folded = match expr {
+        Expr::Assign { value, .. } => {
+            fold_expr(value);
+            None
+        }
+        Expr::Binary {
+            left,
+            operator,
+            right,
+        } => {
+            fold_expr(left);
+            fold_expr(right);
+            match (left.as_ref(), right.as_ref()) {
+                (Expr::Literal(left), Expr::Literal(right)) => fold_binary(left, operator, right),
+                _ => None,
+            }
+        }
+        Expr::Call {
+            callee, arguments, ..
+        } => {
+            fold_expr(callee);
+            arguments.iter_mut().for_each(fold_expr);
+            None
+        }
+        Expr::Get { object, .. } => {
+            fold_expr(object);
+            None
+        }
+        Expr::Grouping(inner) => {
+            fold_expr(inner);
+            match inner.as_ref() {
+                Expr::Literal(value) => Some(value.clone()),
+                _ => None,
+            }
+        }
+        Expr::Index { object, index, .. } => {
+            fold_expr(object);
+            fold_expr(index);
+            None
+        }
+        Expr::Slice {
+            object, start, end, ..
+        } => {
+            fold_expr(object);
+            fold_expr(start);
+            fold_expr(end);
+            None
+        }
+        Expr::IndexSet {
+            object,
+            index,
+            value,
+            ..
+        } => {
+            fold_expr(object);
+            fold_expr(index);
+            fold_expr(value);
+            None
+        }
+        Expr::Lambda(declaration) => {
+            if let Some(declaration) = Rc::get_mut(declaration) {
+                fold_constants(&mut declaration.body);
+            }
+            None
+        }
+        Expr::List(elements) => {
+            elements.iter_mut().for_each(fold_expr);
+            None
+        }
+        Expr::Map { entries, .. } => {
+            for (key, value) in entries {
+                fold_expr(key);
+                fold_expr(value);
+            }
+            None
+        }
+        Expr::Literal(_) | Expr::Super { .. } | Expr::This { .. } | Expr::Variable { .. } => None,
+        Expr::Logical { left, right, .. } => {
+            fold_expr(left);
+            fold_expr(right);
+            None
+        }
+        Expr::Postfix { operand, .. } | Expr::Prefix { operand, .. } => {
+            fold_expr(operand);
+            None
+        }
+        Expr::Set { object, value, .. } => {
+            fold_expr(object);
+            fold_expr(value);
+            None
+        }
+        Expr::Ternary {
+            condition,
+            then_branch,
+            else_branch,
+        } => {
+            fold_expr(condition);
+            fold_expr(then_branch);
+            fold_expr(else_branch);
+            None
+        }
+        Expr::Unary { operator, right } => {
+            fold_expr(right);
+            match right.as_ref() {
+                Expr::Literal(right) => fold_unary(operator, right),
+                _ => None,
+            }
+        }
+    };
+
+    if let Some(value) = folded {
+        *expr = Expr::Literal(value);
+    }
+}
+
+fn fold_unary(operator: &Token, right: &Value) -> Option<Value> {
+    match (operator.kind, right) {
+        (TokenType::Minus, Value::Number(n)) => Some(Value::Number(-n)),
+        (TokenType::Plus, Value::Number(n)) => Some(Value::Number(*n)),
+        (TokenType::Bang, value) => Some(Value::Bool(!is_truthy(value))),
+        _ => None,
+    }
+}
+
+fn fold_binary(left: &Value, operator: &Token, right: &Value) -> Option<Value> {
+    use Value::{Bool, Number, Str};
+
+    let value = match (left, operator.kind, right) {
+        (Number(l), TokenType::Plus, Number(r)) => Number(l + r),
+        (Number(l), TokenType::Minus, Number(r)) => Number(l - r),
+        (Number(l), TokenType::Star, Number(r)) => Number(l * r),
+        (Number(l), TokenType::Slash, Number(r)) if *r != 0.0 => Number(l / r),
+        (Number(l), TokenType::Percent, Number(r)) => Number(l % r),
+        (Number(l), TokenType::StarStar, Number(r)) => Number(l.powf(*r)),
+        (Number(l), TokenType::Greater, Number(r)) => Bool(l > r),
+        (Number(l), TokenType::GreaterEqual, Number(r)) => Bool(l >= r),
+        (Number(l), TokenType::Less, Number(r)) => Bool(l < r),
+        (Number(l), TokenType::LessEqual, Number(r)) => Bool(l <= r),
+        (Str(l), TokenType::Plus, Str(r)) => Str(format!("{l}{r}").into()),
+        (l, TokenType::EqualEqual, r) => Bool(is_equal(l, r)),
+        (l, TokenType::BangEqual, r) => Bool(!is_equal(l, r)),
+        _ => return None,
+    };
+    Some(value)
+}
+
+// Mismas reglas que `Interpreter::is_truthy` e `Interpreter::is_equal`.
+fn is_truthy(value: &Value) -> bool {
+    !matches!(value, Value::Nil | Value::Bool(false))
+}
+
+fn is_equal(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l == r,
+        (Value::Str(l), Value::Str(r)) => l == r,
+        (Value::Bool(l), Value::Bool(r)) => l == r,
+        (Value::Nil, Value::Nil) => true,
+        _ => false,
+    }
+}
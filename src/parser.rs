@@ -1,7 +1,9 @@
+use std::rc::Rc;
+
 use crate::{
-    interpreter::{Expr, Stmt},
+    interpreter::{Expr, FunctionDecl, Stmt, Value, next_expr_id},
     lox::Lox,
-    token::{Literal, Token, TokenType},
+    token::{Token, TokenType},
 };
 
 type ParseFn<'lox> = for<'s> fn(&'s mut Parser<'lox>) -> Result<Expr, ParseError>;
@@ -12,6 +14,8 @@ pub struct ParseError;
 pub struct Parser<'a> {
     tokens: Vec<Token>,
     current: usize,
+    loop_depth: usize,
+    allow_expression: bool,
     lox: &'a mut Lox,
 }
 
@@ -20,39 +24,563 @@ impl<'a> Parser<'a> {
         Self {
             tokens,
             current: 0,
+            loop_depth: 0,
+            allow_expression: false,
             lox,
         }
     }
 
-    pub fn parse(&mut self) -> Option<Vec<Stmt>> {
+    pub fn parse(&mut self) -> Vec<Stmt> {
         let mut statements: Vec<Stmt> = Vec::new();
         while !self.is_at_end() {
-            statements.push(self.statement());
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
         }
-        Some(statements)
+        statements
+    }
+
+    /// Igual que `parse`, pero la última expresión puede omitir el ';' (modo REPL).
+    pub fn parse_repl(&mut self) -> Vec<Stmt> {
+        self.allow_expression = true;
+        self.parse()
     }
 
-    fn statement(&mut self) -> Stmt {
+    fn declaration(&mut self) -> Option<Stmt> {
+        let result = if self._match(&[TokenType::Class]) {
+            self.class_declaration()
+        } else if self.check(TokenType::Fun) && !self.check_next(TokenType::LeftParen) {
+            // `fun (` sin nombre empieza una función anónima, que es una expresión.
+            self.advance();
+            self.function("function")
+                .map(|function| Stmt::Function(Rc::new(function)))
+        } else if self._match(&[TokenType::Var]) {
+            self.var_declaration()
+        } else {
+            self.statement()
+        };
+
+        match result {
+            Ok(statement) => Some(statement),
+            Err(ParseError) => {
+                self.synchronize();
+                None
+            }
+        }
+    }
+
+    fn class_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let name = self
+            .consume(TokenType::Identifier, "Expect class name.")?
+            .clone();
+
+        let superclass = if self._match(&[TokenType::Less]) {
+            let name = self
+                .consume(TokenType::Identifier, "Expect superclass name.")?
+                .clone();
+            Some(Expr::Variable {
+                id: next_expr_id(),
+                name,
+            })
+        } else {
+            None
+        };
+
+        self.consume(TokenType::LeftBrace, "Expect '{' before class body.")?;
+
+        let mut methods = Vec::new();
+        let mut class_methods = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            if self._match(&[TokenType::Class]) {
+                class_methods.push(Rc::new(self.function("method")?));
+            } else {
+                methods.push(Rc::new(self.function("method")?));
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after class body.")?;
+        Ok(Stmt::Class {
+            name,
+            superclass,
+            methods,
+            class_methods,
+        })
+    }
+
+    fn function(&mut self, kind: &str) -> Result<FunctionDecl, ParseError> {
+        let name = self
+            .consume(TokenType::Identifier, &format!("Expect {kind} name."))?
+            .clone();
+        // Un método sin lista de parámetros es un getter: `objeto.nombre` lo llama.
+        if kind == "method" && self.check(TokenType::LeftBrace) {
+            let body = self.function_body(kind)?;
+            return Ok(FunctionDecl {
+                name,
+                params: Vec::new(),
+                variadic: false,
+                getter: true,
+                body,
+            });
+        }
+        self.consume(
+            TokenType::LeftParen,
+            &format!("Expect '(' after {kind} name."),
+        )?;
+        self.function_rest(name, kind)
+    }
+
+    /// Parámetros y cuerpo de una función, a partir del `(` ya consumido.
+    fn function_rest(&mut self, name: Token, kind: &str) -> Result<FunctionDecl, ParseError> {
+        let mut params = Vec::new();
+        let mut variadic = false;
+        if !self.check(TokenType::RightParen) {
+            loop {
+                if params.len() >= 255 {
+                    let token = self.peek().unwrap().clone();
+                    self.error(&token, "Can't have more than 255 parameters.");
+                }
+                variadic = self._match(&[TokenType::DotDotDot]);
+                params.push(
+                    self.consume(TokenType::Identifier, "Expect parameter name.")?
+                        .clone(),
+                );
+                if variadic
+                    || !self._match(&[TokenType::Comma])
+                    || self.check(TokenType::RightParen)
+                {
+                    break;
+                }
+            }
+        }
+        let message = if variadic {
+            "Expect ')' after rest parameter."
+        } else {
+            "Expect ')' after parameters."
+        };
+        self.consume(TokenType::RightParen, message)?;
+
+        let body = self.function_body(kind)?;
+        Ok(FunctionDecl {
+            name,
+            params,
+            variadic,
+            getter: false,
+            body,
+        })
+    }
+
+    fn function_body(&mut self, kind: &str) -> Result<Vec<Stmt>, ParseError> {
+        self.consume(
+            TokenType::LeftBrace,
+            &format!("Expect '{{' before {kind} body."),
+        )?;
+        // `break` y `continue` no atraviesan el cuerpo de una función.
+        let loop_depth = std::mem::take(&mut self.loop_depth);
+        let body = self.block();
+        self.loop_depth = loop_depth;
+        body
+    }
+
+    fn var_declaration(&mut self) -> Result<Stmt, ParseError> {
+        let name = self
+            .consume(TokenType::Identifier, "Expect variable name.")?
+            .clone();
+
+        let initializer = if self._match(&[TokenType::Equal]) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+
+        self.consume(
+            TokenType::Semicolon,
+            "Expect ';' after variable declaration.",
+        )?;
+        Ok(Stmt::Var { name, initializer })
+    }
+
+    fn statement(&mut self) -> Result<Stmt, ParseError> {
+        if self._match(&[TokenType::Break, TokenType::Continue]) {
+            return self.loop_control_statement();
+        }
+        if self._match(&[TokenType::Do]) {
+            return self.do_while_statement();
+        }
+        if self._match(&[TokenType::For]) {
+            return self.for_statement();
+        }
+        if self._match(&[TokenType::If]) {
+            return self.if_statement();
+        }
+        if self._match(&[TokenType::Import]) {
+            return self.import_statement();
+        }
         if self._match(&[TokenType::Print]) {
-            return self.print_statement();
+            return self.print_statement(true);
+        }
+        if self._match(&[TokenType::Write]) {
+            return self.print_statement(false);
+        }
+        if self._match(&[TokenType::Return]) {
+            return self.return_statement();
+        }
+        if self._match(&[TokenType::Switch]) {
+            return self.switch_statement();
+        }
+        if self._match(&[TokenType::While]) {
+            return self.while_statement();
+        }
+        if self._match(&[TokenType::LeftBrace]) {
+            return Ok(Stmt::Block(self.block()?));
         }
         self.expression_statement()
     }
 
-    fn print_statement(&mut self) -> Stmt {
-        let value = self.expression().unwrap();
-        let _ = self.consume(TokenType::Semicolon, "Expect ';' after value.");
-        Stmt::Print(value)
+    fn do_while_statement(&mut self) -> Result<Stmt, ParseError> {
+        let body = Box::new(self.loop_body()?);
+        self.consume(TokenType::While, "Expect 'while' after do body.")?;
+        self.consume(TokenType::LeftParen, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after condition.")?;
+        self.consume(TokenType::Semicolon, "Expect ';' after do-while condition.")?;
+
+        Ok(Stmt::DoWhile { body, condition })
     }
 
-    fn expression_statement(&mut self) -> Stmt {
-        let expr = self.expression().unwrap();
-        let _ = self.consume(TokenType::Semicolon, "Expect ';' after expression");
-        Stmt::Expression(expr)
+    fn for_statement(&mut self) -> Result<Stmt, ParseError> {
+        self.consume(TokenType::LeftParen, "Expect '(' after 'for'.")?;
+
+        let initializer = if self._match(&[TokenType::Semicolon]) {
+            None
+        } else if self._match(&[TokenType::Var]) {
+            Some(self.var_declaration()?)
+        } else {
+            Some(self.expression_statement()?)
+        };
+
+        let condition = if !self.check(TokenType::Semicolon) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(TokenType::Semicolon, "Expect ';' after loop condition.")?;
+
+        let increment = if !self.check(TokenType::RightParen) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(TokenType::RightParen, "Expect ')' after for clauses.")?;
+
+        // `for` se traduce a un `while` envuelto en bloques. El incremento va
+        // aparte del cuerpo para que `continue` no lo salte.
+        let body = self.loop_body()?;
+
+        let condition = condition.unwrap_or(Expr::Literal(Value::Bool(true)));
+        let mut body = Stmt::While {
+            condition,
+            body: Box::new(body),
+            increment,
+        };
+
+        if let Some(initializer) = initializer {
+            body = Stmt::Block(vec![initializer, body]);
+        }
+
+        Ok(body)
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, ParseError> {
+        self.consume(TokenType::LeftParen, "Expect '(' after 'if'.")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after if condition.")?;
+
+        let then_branch = Box::new(self.statement()?);
+        // El `else` se asocia con el `if` más cercano.
+        let else_branch = if self._match(&[TokenType::Else]) {
+            Some(Box::new(self.statement()?))
+        } else {
+            None
+        };
+
+        Ok(Stmt::If {
+            condition,
+            then_branch,
+            else_branch,
+        })
+    }
+
+    fn import_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous().unwrap().clone();
+        let path = self
+            .consume(TokenType::String, "Expect module path after 'import'.")?
+            .clone();
+        self.consume(TokenType::Semicolon, "Expect ';' after module path.")?;
+        Ok(Stmt::Import { keyword, path })
+    }
+
+    fn switch_statement(&mut self) -> Result<Stmt, ParseError> {
+        self.consume(TokenType::LeftParen, "Expect '(' after 'switch'.")?;
+        let subject = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after switch value.")?;
+        self.consume(TokenType::LeftBrace, "Expect '{' before switch body.")?;
+
+        let mut cases = Vec::new();
+        let mut default = None;
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            if self._match(&[TokenType::Case]) {
+                let value = self.expression()?;
+                self.consume(TokenType::Colon, "Expect ':' after case value.")?;
+                cases.push((value, self.case_body()));
+            } else if self._match(&[TokenType::Default]) {
+                let keyword = self.previous().unwrap().clone();
+                self.consume(TokenType::Colon, "Expect ':' after 'default'.")?;
+                if default.is_some() {
+                    self.error(&keyword, "Switch can't have more than one default.");
+                }
+                default = Some(Box::new(self.case_body()));
+            } else {
+                let token = self.peek().unwrap().clone();
+                return Err(self.error(&token, "Expect 'case' or 'default'."));
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after switch body.")?;
+        Ok(Stmt::Switch {
+            subject,
+            cases,
+            default,
+        })
+    }
+
+    /// Las sentencias de una rama, hasta la siguiente etiqueta o el cierre del `switch`.
+    fn case_body(&mut self) -> Stmt {
+        let mut statements = Vec::new();
+        while !self.check(TokenType::Case)
+            && !self.check(TokenType::Default)
+            && !self.check(TokenType::RightBrace)
+            && !self.is_at_end()
+        {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
+        }
+        Stmt::Block(statements)
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt, ParseError> {
+        self.consume(TokenType::LeftParen, "Expect '(' after 'while'.")?;
+        let condition = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ')' after condition.")?;
+        let body = Box::new(self.loop_body()?);
+
+        Ok(Stmt::While {
+            condition,
+            body,
+            increment: None,
+        })
+    }
+
+    fn loop_body(&mut self) -> Result<Stmt, ParseError> {
+        self.loop_depth += 1;
+        let body = self.statement();
+        self.loop_depth -= 1;
+        body
+    }
+
+    fn loop_control_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous().unwrap().clone();
+        if self.loop_depth == 0 {
+            let message = format!("Can't {} outside a loop.", keyword.lexeme);
+            self.error(&keyword, &message);
+        }
+        self.consume(
+            TokenType::Semicolon,
+            &format!("Expect ';' after '{}'.", keyword.lexeme),
+        )?;
+
+        if keyword.kind == TokenType::Break {
+            Ok(Stmt::Break(keyword))
+        } else {
+            Ok(Stmt::Continue(keyword))
+        }
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, ParseError> {
+        let mut statements = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            if let Some(statement) = self.declaration() {
+                statements.push(statement);
+            }
+        }
+
+        self.consume(TokenType::RightBrace, "Expect '}' after block.")?;
+        Ok(statements)
+    }
+
+    /// `print` termina con salto de línea; `write` no.
+    fn print_statement(&mut self, newline: bool) -> Result<Stmt, ParseError> {
+        let expr = self.expression()?;
+        self.consume(TokenType::Semicolon, "Expect ';' after value.")?;
+        Ok(Stmt::Print { expr, newline })
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.previous().unwrap().clone();
+        let value = if !self.check(TokenType::Semicolon) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+
+        self.consume(TokenType::Semicolon, "Expect ';' after return value.")?;
+        Ok(Stmt::Return { keyword, value })
+    }
+
+    fn expression_statement(&mut self) -> Result<Stmt, ParseError> {
+        let expr = self.expression()?;
+        if self.allow_expression && self.is_at_end() {
+            return Ok(Stmt::Expression(expr));
+        }
+        self.consume(TokenType::Semicolon, "Expect ';' after expression.")?;
+        Ok(Stmt::Expression(expr))
     }
 
     fn expression(&mut self) -> Result<Expr, ParseError> {
-        self.equality()
+        self.comma()
+    }
+
+    /// `a, b` evalúa ambos operandos y devuelve el último.
+    fn comma(&mut self) -> Result<Expr, ParseError> {
+        self.left_binop(Self::assignment, &[TokenType::Comma])
+    }
+
+    fn assignment(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.conditional()?;
+
+        if self._match(&[
+            TokenType::Equal,
+            TokenType::PlusEqual,
+            TokenType::MinusEqual,
+            TokenType::StarEqual,
+            TokenType::SlashEqual,
+        ]) {
+            let equals = self.previous().unwrap().clone();
+            let value = self.assignment()?;
+            let operator = Self::compound_operator(&equals);
+
+            match expr {
+                Expr::Variable { name, .. } => {
+                    // `x += e` es `x = x + e`: leer una variable no tiene efectos.
+                    let value = match operator {
+                        Some(operator) => Expr::Binary {
+                            left: Box::new(Expr::Variable {
+                                id: next_expr_id(),
+                                name: name.clone(),
+                            }),
+                            operator,
+                            right: Box::new(value),
+                        },
+                        None => value,
+                    };
+                    return Ok(Expr::Assign {
+                        id: next_expr_id(),
+                        name,
+                        value: Box::new(value),
+                    });
+                }
+                Expr::Get { object, name } => {
+                    return Ok(Expr::Set {
+                        object,
+                        name,
+                        operator,
+                        value: Box::new(value),
+                    });
+                }
+                Expr::Index {
+                    object,
+                    bracket,
+                    index,
+                } => {
+                    return Ok(Expr::IndexSet {
+                        object,
+                        bracket,
+                        index,
+                        operator,
+                        value: Box::new(value),
+                    });
+                }
+                _ => {}
+            }
+
+            // Se reporta sin propagar: el parser no queda en un estado confuso.
+            self.error(&equals, "Invalid assignment target.");
+        }
+
+        Ok(expr)
+    }
+
+    /// Operador binario de una asignación compuesta (`+=` da `+`); `None` para `=`.
+    fn compound_operator(equals: &Token) -> Option<Token> {
+        let (kind, lexeme) = match equals.kind {
+            TokenType::PlusEqual => (TokenType::Plus, "+"),
+            TokenType::MinusEqual => (TokenType::Minus, "-"),
+            TokenType::StarEqual => (TokenType::Star, "*"),
+            TokenType::SlashEqual => (TokenType::Slash, "/"),
+            _ => return None,
+        };
+        let mut operator = Token::new(kind, lexeme, None, equals.line, equals.column);
+        operator.start = equals.start;
+        Some(operator)
+    }
+
+    fn conditional(&mut self) -> Result<Expr, ParseError> {
+        let condition = self.or()?;
+
+        if self._match(&[TokenType::Question]) {
+            let then_branch = self.expression()?;
+            self.consume(
+                TokenType::Colon,
+                "Expect ':' after then branch of conditional expression.",
+            )?;
+            // Asociativo por la derecha: `a ? b : c ? d : e` es `a ? b : (c ? d : e)`.
+            let else_branch = self.conditional()?;
+            return Ok(Expr::Ternary {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            });
+        }
+
+        Ok(condition)
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.and()?;
+        while self._match(&[TokenType::Or]) {
+            let operator = self.previous().unwrap().clone();
+            let right = self.and()?;
+            expr = Expr::Logical {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.equality()?;
+        while self._match(&[TokenType::And]) {
+            let operator = self.previous().unwrap().clone();
+            let right = self.equality()?;
+            expr = Expr::Logical {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
     }
 
     fn equality(&mut self) -> Result<Expr, ParseError> {
@@ -63,14 +591,54 @@ impl<'a> Parser<'a> {
     }
 
     fn comparison(&mut self) -> Result<Expr, ParseError> {
+        const COMPARISONS: [TokenType; 4] = [
+            TokenType::Greater,
+            TokenType::GreaterEqual,
+            TokenType::Less,
+            TokenType::LessEqual,
+        ];
+
+        let expr = self.bit_or()?;
+        if !self._match(&COMPARISONS) {
+            return Ok(expr);
+        }
+        let operator = self.previous().unwrap().clone();
+        let right = self.bit_or()?;
+
+        // `1 < 2 < 3` no significa lo que parece: se exige agruparlo explícitamente.
+        if self._match(&COMPARISONS) {
+            let token = self.previous().unwrap().clone();
+            return Err(self.error(
+                &token,
+                "Chained comparison is not allowed; use explicit parentheses or 'and'.",
+            ));
+        }
+
+        Ok(Expr::Binary {
+            left: Box::new(expr),
+            operator,
+            right: Box::new(right),
+        })
+    }
+
+    // Los operadores de bits se agrupan más fuerte que las comparaciones: `a & b == c`
+    // es `(a & b) == c`.
+    fn bit_or(&mut self) -> Result<Expr, ParseError> {
+        self.left_binop(Self::bit_xor, &[TokenType::Pipe])
+    }
+
+    fn bit_xor(&mut self) -> Result<Expr, ParseError> {
+        self.left_binop(Self::bit_and, &[TokenType::Caret])
+    }
+
+    fn bit_and(&mut self) -> Result<Expr, ParseError> {
+        self.left_binop(Self::shift, &[TokenType::Ampersand])
+    }
+
+    fn shift(&mut self) -> Result<Expr, ParseError> {
         self.left_binop(
             Self::term,
-            &[
-                TokenType::Greater,
-                TokenType::GreaterEqual,
-                TokenType::Less,
-                TokenType::LessEqual,
-            ],
+            &[TokenType::LessLess, TokenType::GreaterGreater],
         )
     }
 
@@ -79,11 +647,29 @@ impl<'a> Parser<'a> {
     }
 
     fn factor(&mut self) -> Result<Expr, ParseError> {
-        self.left_binop(Self::unary, &[TokenType::Slash, TokenType::Star])
+        self.left_binop(
+            Self::power,
+            &[TokenType::Slash, TokenType::Star, TokenType::Percent],
+        )
+    }
+
+    /// `**` es asociativo por la derecha: `2 ** 3 ** 2` es `2 ** (3 ** 2)`.
+    fn power(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.unary()?;
+        if self._match(&[TokenType::StarStar]) {
+            let operator = self.previous().unwrap().clone();
+            let right = self.power()?;
+            return Ok(Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+        Ok(expr)
     }
 
     fn unary(&mut self) -> Result<Expr, ParseError> {
-        if self._match(&[TokenType::Bang, TokenType::Minus]) {
+        if self._match(&[TokenType::Bang, TokenType::Minus, TokenType::Plus]) {
             let operator = self.previous().unwrap().clone();
             let right = self.unary()?;
             return Ok(Expr::Unary {
@@ -91,32 +677,200 @@ impl<'a> Parser<'a> {
                 right: Box::new(right),
             });
         }
-        self.primary()
+        if self._match(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.previous().unwrap().clone();
+            let operand = self.unary()?;
+            self.check_increment_target(&operator, &operand);
+            return Ok(Expr::Prefix {
+                operator,
+                operand: Box::new(operand),
+            });
+        }
+        self.postfix()
+    }
+
+    fn postfix(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.call()?;
+        if self._match(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.previous().unwrap().clone();
+            self.check_increment_target(&operator, &expr);
+            return Ok(Expr::Postfix {
+                operand: Box::new(expr),
+                operator,
+            });
+        }
+        Ok(expr)
+    }
+
+    fn check_increment_target(&mut self, operator: &Token, operand: &Expr) {
+        if !matches!(
+            operand,
+            Expr::Variable { .. } | Expr::Get { .. } | Expr::Index { .. }
+        ) {
+            self.error(operator, "Invalid increment target.");
+        }
+    }
+
+    fn call(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.primary()?;
+        loop {
+            if self._match(&[TokenType::LeftParen]) {
+                expr = self.finish_call(expr)?;
+            } else if self._match(&[TokenType::Dot]) {
+                let name = self
+                    .consume(TokenType::Identifier, "Expect property name after '.'.")?
+                    .clone();
+                expr = Expr::Get {
+                    object: Box::new(expr),
+                    name,
+                };
+            } else if self._match(&[TokenType::LeftBracket]) {
+                let index = self.expression()?;
+                let end = if self._match(&[TokenType::DotDot]) {
+                    Some(self.expression()?)
+                } else {
+                    None
+                };
+                let bracket = self
+                    .consume(TokenType::RightBracket, "Expect ']' after index.")?
+                    .clone();
+                expr = match end {
+                    Some(end) => Expr::Slice {
+                        object: Box::new(expr),
+                        bracket,
+                        start: Box::new(index),
+                        end: Box::new(end),
+                    },
+                    None => Expr::Index {
+                        object: Box::new(expr),
+                        bracket,
+                        index: Box::new(index),
+                    },
+                };
+            } else {
+                break;
+            }
+        }
+        Ok(expr)
+    }
+
+    fn finish_call(&mut self, callee: Expr) -> Result<Expr, ParseError> {
+        let mut arguments = Vec::new();
+        if !self.check(TokenType::RightParen) {
+            loop {
+                if arguments.len() >= 255 {
+                    let token = self.peek().unwrap().clone();
+                    self.error(&token, "Can't have more than 255 arguments.");
+                }
+                arguments.push(self.assignment()?);
+                // Se admite una coma final: `f(1, 2,)`.
+                if !self._match(&[TokenType::Comma]) || self.check(TokenType::RightParen) {
+                    break;
+                }
+            }
+        }
+
+        let paren = self
+            .consume(TokenType::RightParen, "Expect ')' after arguments.")?
+            .clone();
+
+        Ok(Expr::Call {
+            callee: Box::new(callee),
+            paren,
+            arguments,
+        })
     }
 
     fn primary(&mut self) -> Result<Expr, ParseError> {
+        if self._match(&[TokenType::Fun]) {
+            let keyword = self.previous().unwrap().clone();
+            self.consume(TokenType::LeftParen, "Expect '(' after 'fun'.")?;
+            let function = self.function_rest(keyword, "function")?;
+            return Ok(Expr::Lambda(Rc::new(function)));
+        }
         if self._match(&[TokenType::False]) {
-            return Ok(Expr::Literal(Literal::Bool(false)));
+            return Ok(Expr::Literal(Value::Bool(false)));
         };
         if self._match(&[TokenType::True]) {
-            return Ok(Expr::Literal(Literal::Bool(true)));
+            return Ok(Expr::Literal(Value::Bool(true)));
         };
         if self._match(&[TokenType::Nil]) {
-            return Ok(Expr::Literal(Literal::Nil));
+            return Ok(Expr::Literal(Value::Nil));
         };
 
         if self._match(&[TokenType::Number, TokenType::String]) {
-            return Ok(Expr::Literal(
-                self.previous().unwrap().literal.clone().unwrap(),
-            ));
+            // El valor se construye una sola vez, aquí, y no en cada evaluación.
+            let literal = self.previous().unwrap().literal.clone().unwrap();
+            return Ok(Expr::Literal(literal.into()));
         };
 
+        if self._match(&[TokenType::Super]) {
+            let keyword = self.previous().unwrap().clone();
+            self.consume(TokenType::Dot, "Expect '.' after 'super'.")?;
+            let method = self
+                .consume(TokenType::Identifier, "Expect superclass method name.")?
+                .clone();
+            return Ok(Expr::Super {
+                id: next_expr_id(),
+                keyword,
+                method,
+            });
+        }
+
+        if self._match(&[TokenType::This]) {
+            return Ok(Expr::This {
+                id: next_expr_id(),
+                keyword: self.previous().unwrap().clone(),
+            });
+        }
+
+        if self._match(&[TokenType::Identifier]) {
+            return Ok(Expr::Variable {
+                id: next_expr_id(),
+                name: self.previous().unwrap().clone(),
+            });
+        }
+
         if self._match(&[TokenType::LeftParen]) {
             let expr = self.expression()?;
-            let _ = self.consume(TokenType::RightParen, "Expect ')' after expression.");
+            self.consume(TokenType::RightParen, "Expect ')' after expression.")?;
             return Ok(Expr::Grouping(Box::new(expr)));
         }
 
+        // En posición de expresión, `{` abre un mapa; los bloques se reconocen antes,
+        // en `statement`.
+        if self._match(&[TokenType::LeftBrace]) {
+            let brace = self.previous().unwrap().clone();
+            let mut entries = Vec::new();
+            if !self.check(TokenType::RightBrace) {
+                loop {
+                    let key = self.assignment()?;
+                    self.consume(TokenType::Colon, "Expect ':' after map key.")?;
+                    let value = self.assignment()?;
+                    entries.push((key, value));
+                    if !self._match(&[TokenType::Comma]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RightBrace, "Expect '}' after map entries.")?;
+            return Ok(Expr::Map { brace, entries });
+        }
+
+        if self._match(&[TokenType::LeftBracket]) {
+            let mut elements = Vec::new();
+            if !self.check(TokenType::RightBracket) {
+                loop {
+                    elements.push(self.assignment()?);
+                    if !self._match(&[TokenType::Comma]) {
+                        break;
+                    }
+                }
+            }
+            self.consume(TokenType::RightBracket, "Expect ']' after list elements.")?;
+            return Ok(Expr::List(elements));
+        }
+
         let error = {
             let token = self.peek().unwrap().clone();
             self.error(&token, "Expect expression.")
@@ -139,7 +893,7 @@ impl<'a> Parser<'a> {
         ParseError
     }
 
-    fn _synchronize(&mut self) {
+    fn synchronize(&mut self) {
         self.advance();
 
         while !self.is_at_end() {
@@ -152,10 +906,14 @@ impl<'a> Parser<'a> {
                     TokenType::Class
                     | TokenType::Fun
                     | TokenType::Var
+                    | TokenType::Do
                     | TokenType::For
                     | TokenType::If
+                    | TokenType::Import
                     | TokenType::While
                     | TokenType::Print
+                    | TokenType::Write
+                    | TokenType::Switch
                     | TokenType::Return,
                 ) => return,
                 _ => {
@@ -204,6 +962,12 @@ impl<'a> Parser<'a> {
         self.peek().map(|t| t.kind == kind).unwrap_or(false)
     }
 
+    fn check_next(&mut self, kind: TokenType) -> bool {
+        self.tokens
+            .get(self.current + 1)
+            .is_some_and(|t| t.kind == kind)
+    }
+
     fn peek(&mut self) -> Option<&Token> {
         self.tokens.get(self.current)
     }
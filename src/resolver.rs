@@ -0,0 +1,355 @@
+use std::collections::HashMap;
+
+use crate::{
+    interpreter::{Expr, ExprId, FunctionDecl, Interpreter, Stmt},
+    lox::Lox,
+    token::Token,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum FunctionType {
+    None,
+    Function,
+    Initializer,
+    Method,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ClassType {
+    None,
+    Class,
+    Subclass,
+    /// Dentro de un método de clase, donde no hay `this` ni `super`.
+    ClassMethod,
+}
+
+pub struct Resolver<'a> {
+    interpreter: &'a mut Interpreter,
+    lox: &'a mut Lox,
+    scopes: Vec<HashMap<String, bool>>,
+    current_function: FunctionType,
+    current_class: ClassType,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(interpreter: &'a mut Interpreter, lox: &'a mut Lox) -> Self {
+        Self {
+            interpreter,
+            lox,
+            scopes: Vec::new(),
+            current_function: FunctionType::None,
+            current_class: ClassType::None,
+        }
+    }
+
+    pub fn resolve(&mut self, statements: &[Stmt]) {
+        let mut warned = false;
+        for (i, statement) in statements.iter().enumerate() {
+            self.resolve_stmt(statement);
+            if let Stmt::Return { keyword, .. } = statement
+                && i + 1 < statements.len()
+                && !warned
+            {
+                self.lox
+                    .warning_at(keyword, "Unreachable code after 'return'.");
+                warned = true;
+            }
+        }
+    }
+
+    fn resolve_stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Break(_) | Stmt::Continue(_) => {}
+            Stmt::Expression(expr) | Stmt::Print { expr, .. } => self.resolve_expr(expr),
+            Stmt::Function(declaration) => {
+                self.declare(&declaration.name);
+                self.define(&declaration.name);
+                self.resolve_function(declaration, FunctionType::Function);
+            }
+            Stmt::Return { keyword, value } => {
+                if self.current_function == FunctionType::None {
+                    self.lox
+                        .error_at(keyword, "Can't return from top-level code.");
+                }
+                if let Some(value) = value {
+                    if self.current_function == FunctionType::Initializer {
+                        self.lox
+                            .error_at(keyword, "Can't return a value from an initializer.");
+                    }
+                    self.resolve_expr(value);
+                }
+            }
+            Stmt::Block(statements) => {
+                self.begin_scope();
+                self.resolve(statements);
+                self.end_scope();
+            }
+            Stmt::Class {
+                name,
+                superclass,
+                methods,
+                class_methods,
+            } => {
+                let enclosing_class = self.current_class;
+                self.current_class = ClassType::Class;
+
+                self.declare(name);
+                self.define(name);
+
+                if let Some(superclass) = superclass {
+                    if let Expr::Variable {
+                        name: superclass_name,
+                        ..
+                    } = superclass
+                        && superclass_name.lexeme == name.lexeme
+                    {
+                        self.lox
+                            .error_at(superclass_name, "A class can't inherit from itself.");
+                    }
+                    self.current_class = ClassType::Subclass;
+                    self.resolve_expr(superclass);
+
+                    self.begin_scope();
+                    self.scopes
+                        .last_mut()
+                        .unwrap()
+                        .insert("super".to_string(), true);
+                }
+
+                // Los métodos de clase capturan el mismo entorno que los de instancia,
+                // pero sin el ámbito de `this`.
+                let class_type = std::mem::replace(&mut self.current_class, ClassType::ClassMethod);
+                for method in class_methods {
+                    self.resolve_function(method, FunctionType::Method);
+                }
+                self.current_class = class_type;
+
+                self.begin_scope();
+                self.scopes
+                    .last_mut()
+                    .unwrap()
+                    .insert("this".to_string(), true);
+                for method in methods {
+                    let kind = if method.name.lexeme == "init" {
+                        FunctionType::Initializer
+                    } else {
+                        FunctionType::Method
+                    };
+                    self.resolve_function(method, kind);
+                }
+                self.end_scope();
+
+                if superclass.is_some() {
+                    self.end_scope();
+                }
+
+                self.current_class = enclosing_class;
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_stmt(then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.resolve_stmt(else_branch);
+                }
+            }
+            Stmt::Switch {
+                subject,
+                cases,
+                default,
+            } => {
+                self.resolve_expr(subject);
+                for (value, body) in cases {
+                    self.resolve_expr(value);
+                    self.resolve_stmt(body);
+                }
+                if let Some(default) = default {
+                    self.resolve_stmt(default);
+                }
+            }
+            Stmt::Import { .. } => {}
+            Stmt::Var { name, initializer } => {
+                self.declare(name);
+                if let Some(initializer) = initializer {
+                    self.resolve_expr(initializer);
+                }
+                self.define(name);
+            }
+            Stmt::DoWhile { body, condition } => {
+                self.resolve_stmt(body);
+                self.resolve_expr(condition);
+            }
+            Stmt::While {
+                condition,
+                body,
+                increment,
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_stmt(body);
+                if let Some(increment) = increment {
+                    self.resolve_expr(increment);
+                }
+            }
+        }
+    }
+
+    fn resolve_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Assign { id, name, value } => {
+                self.resolve_expr(value);
+                self.resolve_local(*id, name);
+            }
+            Expr::Binary { left, right, .. } | Expr::Logical { left, right, .. } => {
+                self.resolve_expr(left);
+                self.resolve_expr(right);
+            }
+            Expr::Call {
+                callee, arguments, ..
+            } => {
+                self.resolve_expr(callee);
+                for argument in arguments {
+                    self.resolve_expr(argument);
+                }
+            }
+            Expr::Get { object, .. } => self.resolve_expr(object),
+            Expr::Set { object, value, .. } => {
+                self.resolve_expr(value);
+                self.resolve_expr(object);
+            }
+            Expr::Grouping(inner) => self.resolve_expr(inner),
+            Expr::Index { object, index, .. } => {
+                self.resolve_expr(object);
+                self.resolve_expr(index);
+            }
+            Expr::Slice {
+                object, start, end, ..
+            } => {
+                self.resolve_expr(object);
+                self.resolve_expr(start);
+                self.resolve_expr(end);
+            }
+            Expr::IndexSet {
+                object,
+                index,
+                value,
+                ..
+            } => {
+                self.resolve_expr(value);
+                self.resolve_expr(object);
+                self.resolve_expr(index);
+            }
+            Expr::Lambda(declaration) => {
+                self.resolve_function(declaration, FunctionType::Function);
+            }
+            Expr::List(elements) => {
+                for element in elements {
+                    self.resolve_expr(element);
+                }
+            }
+            Expr::Literal(_) => {}
+            Expr::Map { entries, .. } => {
+                for (key, value) in entries {
+                    self.resolve_expr(key);
+                    self.resolve_expr(value);
+                }
+            }
+            Expr::Super { id, keyword, .. } => match self.current_class {
+                ClassType::None => self
+                    .lox
+                    .error_at(keyword, "Can't use 'super' outside of a class."),
+                ClassType::Class => self
+                    .lox
+                    .error_at(keyword, "Can't use 'super' in a class with no superclass."),
+                ClassType::Subclass => self.resolve_local(*id, keyword),
+                ClassType::ClassMethod => self
+                    .lox
+                    .error_at(keyword, "Can't use 'super' in a class method."),
+            },
+            Expr::Ternary {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.resolve_expr(condition);
+                self.resolve_expr(then_branch);
+                self.resolve_expr(else_branch);
+            }
+            Expr::This { id, keyword } => {
+                if self.current_class == ClassType::None {
+                    self.lox
+                        .error_at(keyword, "Can't use 'this' outside of a class.");
+                    return;
+                }
+                if self.current_class == ClassType::ClassMethod {
+                    self.lox
+                        .error_at(keyword, "Can't use 'this' in a class method.");
+                    return;
+                }
+                self.resolve_local(*id, keyword);
+            }
+            Expr::Postfix { operand, .. } | Expr::Prefix { operand, .. } => {
+                self.resolve_expr(operand)
+            }
+            Expr::Unary { right, .. } => self.resolve_expr(right),
+            Expr::Variable { id, name } => {
+                if let Some(false) = self.scopes.last().and_then(|s| s.get(&name.lexeme)) {
+                    self.lox
+                        .error_at(name, "Can't read local variable in its own initializer.");
+                }
+                self.resolve_local(*id, name);
+            }
+        }
+    }
+
+    fn resolve_function(&mut self, function: &FunctionDecl, kind: FunctionType) {
+        let enclosing_function = self.current_function;
+        self.current_function = kind;
+
+        self.begin_scope();
+        for param in &function.params {
+            self.declare(param);
+            self.define(param);
+        }
+        self.resolve(&function.body);
+        self.end_scope();
+
+        self.current_function = enclosing_function;
+    }
+
+    fn resolve_local(&mut self, id: ExprId, name: &Token) {
+        for (depth, scope) in self.scopes.iter().rev().enumerate() {
+            if scope.contains_key(&name.lexeme) {
+                self.interpreter.resolve(id, depth);
+                return;
+            }
+        }
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn end_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &Token) {
+        let Some(scope) = self.scopes.last_mut() else {
+            return;
+        };
+        if scope.contains_key(&name.lexeme) {
+            self.lox
+                .error_at(name, "Already a variable with this name in this scope.");
+        }
+        scope.insert(name.lexeme.clone(), false);
+    }
+
+    fn define(&mut self, name: &Token) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.lexeme.clone(), true);
+        }
+    }
+}
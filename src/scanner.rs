@@ -6,10 +6,13 @@ use crate::{
 };
 
 pub struct Scanner<'a> {
-    source: String,
+    // Indexar por carácter hace que `peek` y `advance` sean O(1) también con texto no ASCII.
+    source: Vec<char>,
     start: usize,
     current: usize,
     line: usize,
+    line_start: usize,
+    start_column: usize,
     tokens: Vec<Token>,
     lox: &'a mut Lox,
 }
@@ -17,31 +20,41 @@ pub struct Scanner<'a> {
 pub static KEYWORDS: LazyLock<HashMap<&str, TokenType>> = std::sync::LazyLock::new(|| {
     let mut m = HashMap::new();
     m.insert("and", TokenType::And);
+    m.insert("break", TokenType::Break);
+    m.insert("case", TokenType::Case);
     m.insert("class", TokenType::Class);
+    m.insert("continue", TokenType::Continue);
+    m.insert("default", TokenType::Default);
+    m.insert("do", TokenType::Do);
     m.insert("else", TokenType::Else);
     m.insert("false", TokenType::False);
     m.insert("fun", TokenType::Fun);
     m.insert("for", TokenType::For);
     m.insert("if", TokenType::If);
+    m.insert("import", TokenType::Import);
     m.insert("nil", TokenType::Nil);
     m.insert("or", TokenType::Or);
     m.insert("print", TokenType::Print);
     m.insert("return", TokenType::Return);
     m.insert("super", TokenType::Super);
+    m.insert("switch", TokenType::Switch);
     m.insert("this", TokenType::This);
     m.insert("true", TokenType::True);
     m.insert("var", TokenType::Var);
     m.insert("while", TokenType::While);
+    m.insert("write", TokenType::Write);
     m
 });
 
 impl<'a> Scanner<'a> {
     pub fn new(source: impl Into<String>, lox: &'a mut Lox) -> Scanner<'a> {
         Scanner {
-            source: source.into(),
+            source: source.into().chars().collect(),
             start: 0,
             current: 0,
             line: 1,
+            line_start: 0,
+            start_column: 1,
             tokens: vec![],
             lox,
         }
@@ -50,11 +63,13 @@ impl<'a> Scanner<'a> {
     pub fn scan_tokens(&mut self) -> Vec<Token> {
         while !self.is_at_end() {
             self.start = self.current;
+            self.start_column = self.column();
             self.scan_token();
         }
 
-        self.tokens
-            .push(Token::new(TokenType::Eof, "", None, self.line));
+        let mut eof = Token::new(TokenType::Eof, "", None, self.line, self.column());
+        eof.start = self.current;
+        self.tokens.push(eof);
 
         std::mem::take(&mut self.tokens)
     }
@@ -67,12 +82,56 @@ impl<'a> Scanner<'a> {
             ')' => self.add_token(RightParen, None),
             '{' => self.add_token(LeftBrace, None),
             '}' => self.add_token(RightBrace, None),
+            '[' => self.add_token(LeftBracket, None),
+            ']' => self.add_token(RightBracket, None),
+            ':' => self.add_token(Colon, None),
             ',' => self.add_token(Comma, None),
-            '.' => self.add_token(Dot, None),
-            '-' => self.add_token(Minus, None),
-            '+' => self.add_token(Plus, None),
+            '.' => {
+                if self.peek() == '.' && self.peek_next() == '.' {
+                    self.current += 2;
+                    self.add_token(DotDotDot, None);
+                } else if self.match_char('.') {
+                    self.add_token(DotDot, None);
+                } else {
+                    self.add_token(Dot, None);
+                }
+            }
+            '-' => {
+                let kind = if self.match_char('-') {
+                    MinusMinus
+                } else if self.match_char('=') {
+                    MinusEqual
+                } else {
+                    Minus
+                };
+                self.add_token(kind, None);
+            }
+            '+' => {
+                let kind = if self.match_char('+') {
+                    PlusPlus
+                } else if self.match_char('=') {
+                    PlusEqual
+                } else {
+                    Plus
+                };
+                self.add_token(kind, None);
+            }
+            '%' => self.add_token(Percent, None),
+            '&' => self.add_token(Ampersand, None),
+            '|' => self.add_token(Pipe, None),
+            '^' => self.add_token(Caret, None),
+            '?' => self.add_token(Question, None),
             ';' => self.add_token(Semicolon, None),
-            '*' => self.add_token(Star, None),
+            '*' => {
+                let kind = if self.match_char('*') {
+                    StarStar
+                } else if self.match_char('=') {
+                    StarEqual
+                } else {
+                    Star
+                };
+                self.add_token(kind, None);
+            }
             '!' => {
                 let kind = if self.match_char('=') {
                     BangEqual
@@ -92,6 +151,8 @@ impl<'a> Scanner<'a> {
             '<' => {
                 let kind = if self.match_char('=') {
                     LessEqual
+                } else if self.match_char('<') {
+                    LessLess
                 } else {
                     Less
                 };
@@ -100,6 +161,8 @@ impl<'a> Scanner<'a> {
             '>' => {
                 let kind = if self.match_char('=') {
                     GreaterEqual
+                } else if self.match_char('>') {
+                    GreaterGreater
                 } else {
                     Greater
                 };
@@ -107,35 +170,63 @@ impl<'a> Scanner<'a> {
             }
             '/' => {
                 if self.match_char('/') {
-                    while self.peek() != '\n' && !self.is_at_end() {
+                    while !matches!(self.peek(), '\n' | '\r') && !self.is_at_end() {
                         self.advance();
                     }
+                } else if self.match_char('*') {
+                    self.block_comment();
+                } else if self.match_char('=') {
+                    self.add_token(SlashEqual, None);
                 } else {
                     self.add_token(Slash, None);
                 }
             }
             ' ' => {}
+            // `\r\n` cuenta como un único salto; un `\r` suelto también es un salto.
+            '\r' if self.peek() != '\n' => self.newline(),
             '\r' => {}
             '\t' => {}
-            '\n' => {
-                self.line += 1;
-            }
+            '\n' => self.newline(),
             '"' => self.string(),
             _ => {
                 if c.is_ascii_digit() {
                     self.number();
-                } else if c.is_alphabetic() {
+                } else if c.is_alphabetic() || c == '_' {
                     self.identifier();
                 } else {
-                    self.lox.error(self.line, "Unexpected character");
+                    self.lox.error(
+                        self.line,
+                        self.start_column,
+                        &format!("Unexpected character '{c}'."),
+                    );
                 }
             }
         }
     }
 
     fn add_token(&mut self, kind: TokenType, literal: Option<Literal>) {
-        let text = &self.source[self.start..self.current];
-        self.tokens.push(Token::new(kind, text, literal, self.line))
+        let text = self.text(self.start, self.current);
+        let mut token = Token::new(kind, text, literal, self.line, self.start_column);
+        token.start = self.start;
+        self.tokens.push(token);
+    }
+
+    fn error(&mut self, message: &str) {
+        self.lox.error(self.line, self.column(), message);
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        self.source[start..end].iter().collect()
+    }
+
+    /// Columna (en caracteres, empezando en 1) de la posición actual.
+    fn column(&self) -> usize {
+        self.current - self.line_start + 1
+    }
+
+    fn newline(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
     }
 
     #[inline]
@@ -145,19 +236,25 @@ impl<'a> Scanner<'a> {
 
     #[inline]
     fn peek(&self) -> char {
-        self.source[self.current..].chars().next().unwrap_or('\0')
+        self.peek_at(0)
     }
 
     #[inline]
     fn peek_next(&self) -> char {
-        let mut it = self.source[self.current..].chars();
-        let _ = it.next(); // salta actual
-        it.next().unwrap_or('\0')
+        self.peek_at(1)
+    }
+
+    #[inline]
+    fn peek_at(&self, offset: usize) -> char {
+        self.source
+            .get(self.current + offset)
+            .copied()
+            .unwrap_or('\0')
     }
 
     fn match_char(&mut self, expected: char) -> bool {
-        if self.peek() == expected {
-            self.current += expected.len_utf8();
+        if !self.is_at_end() && self.peek() == expected {
+            self.current += 1;
             return true;
         }
         false
@@ -166,55 +263,160 @@ impl<'a> Scanner<'a> {
     /// Avanza un carácter y lo devuelve. Si está al final, retorna '\0'.
     #[inline]
     fn advance(&mut self) -> char {
-        let ch = self.peek();
-        if ch != '\0' {
-            self.current += ch.len_utf8(); // mover cursor en bytes
+        // Un '\0' literal en el código también debe consumirse; si no, el bucle no avanza.
+        match self.source.get(self.current) {
+            Some(&ch) => {
+                self.current += 1;
+                ch
+            }
+            None => '\0',
         }
-        ch
     }
 
     fn string(&mut self) {
+        let mut value = String::new();
         while self.peek() != '"' && !self.is_at_end() {
-            if self.peek() == '\n' {
-                self.line += 1;
+            match self.advance() {
+                '\\' => {
+                    if let Some(c) = self.escape() {
+                        value.push(c);
+                    }
+                }
+                c => {
+                    if c == '\n' || (c == '\r' && self.peek() != '\n') {
+                        self.newline();
+                    }
+                    value.push(c);
+                }
             }
-            self.advance();
         }
 
         if self.is_at_end() {
-            self.lox.error(self.line, "Unterminated string.");
+            self.error("Unterminated string.");
             return;
         }
         self.advance();
-        let value = &self.source[self.start + 1..self.current - 1];
-        self.add_token(TokenType::String, Some(Literal::Str(value.to_string())));
+        self.add_token(TokenType::String, Some(Literal::Str(value)));
+    }
+
+    /// Decodifica la secuencia de escape que sigue a una barra invertida.
+    fn escape(&mut self) -> Option<char> {
+        if self.is_at_end() {
+            return None;
+        }
+        match self.advance() {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            '0' => Some('\0'),
+            c => {
+                if c == '\n' {
+                    self.newline();
+                }
+                self.error(&format!("Invalid escape sequence '\\{c}'."));
+                None
+            }
+        }
+    }
+
+    fn block_comment(&mut self) {
+        let mut depth = 1;
+        while depth > 0 {
+            if self.is_at_end() {
+                self.error("Unterminated block comment.");
+                return;
+            }
+            match self.advance() {
+                '/' if self.match_char('*') => depth += 1,
+                '*' if self.match_char('/') => depth -= 1,
+                '\n' => self.newline(),
+                '\r' if self.peek() != '\n' => self.newline(),
+                _ => {}
+            }
+        }
     }
 
     fn number(&mut self) {
-        while self.peek().is_ascii_digit() {
+        if self.source[self.start] == '0' && matches!(self.peek(), 'x' | 'X') {
             self.advance();
+            return self.hex_number();
         }
 
+        self.digits();
+
         if self.peek() == '.' && self.peek_next().is_ascii_digit() {
             self.advance();
-            while self.peek().is_ascii_digit() {
+            self.digits();
+        }
+
+        // Exponente opcional: `e`/`E`, signo opcional y al menos un dígito.
+        if matches!(self.peek(), 'e' | 'E') {
+            let has_sign = matches!(self.peek_next(), '+' | '-');
+            let first_digit = if has_sign {
+                self.peek_at(2)
+            } else {
+                self.peek_next()
+            };
+            if first_digit.is_ascii_digit() {
                 self.advance();
+                if has_sign {
+                    self.advance();
+                }
+                self.digits();
             }
         }
 
-        let value = &self.source[self.start..self.current];
+        let value = self.text(self.start, self.current).replace('_', "");
         self.add_token(
             TokenType::Number,
             Some(Literal::Number(value.parse().unwrap_or(0.0))),
         );
     }
 
+    fn hex_number(&mut self) {
+        while self.peek().is_ascii_hexdigit() {
+            self.advance();
+        }
+
+        let digits = self.text(self.start + 2, self.current);
+        let value = i64::from_str_radix(&digits, 16).unwrap_or_else(|_| {
+            self.error("Invalid hex literal.");
+            // Se emite igual un número para que el parser no informe otro error.
+            0
+        });
+        self.add_token(TokenType::Number, Some(Literal::Number(value as f64)));
+    }
+
+    /// Consume dígitos, permitiendo un `_` como separador entre dos de ellos.
+    fn digits(&mut self) {
+        loop {
+            match self.peek() {
+                c if c.is_ascii_digit() => {}
+                '_' if self.peek_next().is_ascii_digit() => {}
+                '_' if self.peek_next() == '_' => {
+                    while self.peek() == '_' {
+                        self.advance();
+                    }
+                    self.error("Invalid digit separator '__' in number.");
+                    continue;
+                }
+                _ => break,
+            }
+            self.advance();
+        }
+    }
+
     fn identifier(&mut self) {
-        while self.peek().is_alphanumeric() {
+        while self.peek().is_alphanumeric() || self.peek() == '_' {
             self.advance();
         }
-        let text = &self.source[self.start..self.current];
-        let kind = KEYWORDS.get(text).copied().unwrap_or(TokenType::Identifier);
+        let text = self.text(self.start, self.current);
+        let kind = KEYWORDS
+            .get(text.as_str())
+            .copied()
+            .unwrap_or(TokenType::Identifier);
         self.add_token(kind, None);
     }
 }
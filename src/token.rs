@@ -1,5 +1,7 @@
-use core::fmt;
-use std::fmt::Display;
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
 
 use crate::interpreter::Value;
 
@@ -9,10 +11,20 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    Ampersand,
+    Caret,
+    LeftBracket,
+    RightBracket,
+    Colon,
     Comma,
     Dot,
+    DotDot,
+    DotDotDot,
     Minus,
+    Percent,
+    Pipe,
     Plus,
+    Question,
     Semicolon,
     Slash,
     Star,
@@ -24,8 +36,17 @@ pub enum TokenType {
     EqualEqual,
     Greater,
     GreaterEqual,
+    GreaterGreater,
     Less,
     LessEqual,
+    LessLess,
+    MinusEqual,
+    MinusMinus,
+    PlusEqual,
+    PlusPlus,
+    SlashEqual,
+    StarEqual,
+    StarStar,
 
     // Literals.
     Identifier,
@@ -34,26 +55,34 @@ pub enum TokenType {
 
     // Keywords.
     And,
+    Break,
+    Case,
     Class,
+    Continue,
+    Default,
+    Do,
     Else,
     False,
     Fun,
     For,
     If,
+    Import,
     Nil,
     Or,
     Print,
     Return,
     Super,
+    Switch,
     This,
     True,
     Var,
     While,
+    Write,
 
     Eof,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum Literal {
     Number(f64),
     Str(String),
@@ -61,23 +90,57 @@ pub enum Literal {
     Nil,
 }
 
+// Los números se comparan por sus bits: así `Eq` y `Hash` son coherentes
+// (NaN es igual a sí mismo, y 0.0 y -0.0 son literales distintos).
+impl PartialEq for Literal {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Literal::Number(a), Literal::Number(b)) => a.to_bits() == b.to_bits(),
+            (Literal::Str(a), Literal::Str(b)) => a == b,
+            (Literal::Bool(a), Literal::Bool(b)) => a == b,
+            (Literal::Nil, Literal::Nil) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Literal {}
+
+impl Hash for Literal {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Literal::Number(n) => n.to_bits().hash(state),
+            Literal::Str(s) => s.hash(state),
+            Literal::Bool(b) => b.hash(state),
+            Literal::Nil => {}
+        }
+    }
+}
+
 impl From<Literal> for Value {
     fn from(literal: Literal) -> Self {
         match literal {
             Literal::Number(n) => Value::Number(n),
-            Literal::Str(s) => Value::Str(s),
+            Literal::Str(s) => Value::Str(s.into()),
             Literal::Bool(b) => Value::Bool(b),
             Literal::Nil => Value::Nil,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Token {
     pub kind: TokenType,
     pub lexeme: String,
     pub literal: Option<Literal>,
     pub line: usize,
+    pub column: usize,
+    /// Inicio del token en el código fuente. Es un desplazamiento en caracteres, no en
+    /// bytes: el scanner indexa el código como `Vec<char>`.
+    pub start: usize,
+    /// Longitud del token en caracteres.
+    pub len: usize,
 }
 
 impl Token {
@@ -86,17 +149,22 @@ impl Token {
         lexeme: impl Into<String>,
         literal: Option<Literal>,
         line: usize,
+        column: usize,
     ) -> Self {
+        let lexeme = lexeme.into();
         Self {
             kind,
-            lexeme: lexeme.into(),
+            len: lexeme.chars().count(),
+            lexeme,
             literal,
             line,
+            column,
+            start: 0,
         }
     }
 }
 
-impl Display for Token {
+impl fmt::Display for Token {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?} {} {:?}", self.kind, self.lexeme, self.literal)
     }
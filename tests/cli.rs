@@ -0,0 +1,195 @@
+//! Pruebas del binario `jlox`: REPL y opciones de la línea de comandos.
+
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn jlox(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_jlox"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn repl_continues_incomplete_input() {
+    let output = jlox(
+        &[],
+        "if (true) {\n  print \"inside\";\n}\nprint \"after\";\n",
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout, "> ... ... inside\n> after\n> ");
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn runtime_errors_report_the_line_of_the_operator() {
+    let output = jlox(&["-"], "var a = nil;\nprint 1 *\n  a;\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(
+        stderr,
+        "[line 2] Runtime error: Operands must be numbers.\n"
+    );
+    assert_eq!(output.status.code(), Some(70));
+}
+
+#[test]
+fn runtime_errors_in_calls_report_the_closing_paren() {
+    let output = jlox(&["-"], "fun f(a) {}\nf(\n  1,\n  2\n);\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(
+        stderr,
+        "[line 5] Runtime error: Expected 1 arguments but got 2.\n"
+    );
+}
+
+#[test]
+fn repl_hides_implicit_nil_results() {
+    let output = jlox(&[], "fun f() {}\nf()\nnil\n1 + 1\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout, "> > > nil\n> 2\n> ");
+}
+
+#[test]
+fn time_reports_each_phase_on_stderr() {
+    let output = jlox(&["--time", "-"], "print 1 + 2;\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(stdout, "3\n");
+    let phases: Vec<&str> = stderr
+        .lines()
+        .map(|line| {
+            let (phase, ms) = line
+                .strip_prefix("[time] ")
+                .and_then(|line| line.strip_suffix(" ms"))
+                .and_then(|line| line.split_once(": "))
+                .unwrap();
+            assert!(ms.parse::<f64>().unwrap() >= 0.0);
+            phase
+        })
+        .collect();
+    assert_eq!(phases, ["scan", "parse", "resolve", "interpret"]);
+}
+
+#[test]
+fn syntax_errors_underline_the_token() {
+    let output = jlox(&["-"], "var x = 1;\n\tprint x + ;\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(
+        stderr,
+        "[line 2, col 12] Error at ';': Expect expression.\n\tprint x + ;\n\t          ^\n"
+    );
+}
+
+#[test]
+fn tokens_dumps_the_scanner_output() {
+    let output = jlox(&["--tokens", "-"], "var a = 1;\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout,
+        "Var var None\nIdentifier a None\nEqual = None\nNumber 1 Some(Number(1.0))\n\
+         Semicolon ; None\nEof  None\n"
+    );
+}
+
+#[test]
+fn missing_file_exits_with_66() {
+    let output = jlox(&["no/such/script.lox"], "");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(66));
+    assert_eq!(
+        stderr,
+        "Error: could not read 'no/such/script.lox': file not found\n"
+    );
+}
+
+#[test]
+fn check_reports_errors_without_running() {
+    let valid = jlox(&["--check", "-"], "print \"ran\";\n");
+    assert_eq!(valid.status.code(), Some(0));
+    assert!(valid.stdout.is_empty());
+
+    let syntax = jlox(&["--check", "-"], "print ;\n");
+    assert_eq!(syntax.status.code(), Some(65));
+
+    // Los errores del resolver también cuentan.
+    let resolve = jlox(&["--check", "-"], "return 1;\n");
+    let stderr = String::from_utf8(resolve.stderr).unwrap();
+    assert_eq!(resolve.status.code(), Some(65));
+    assert!(
+        stderr.starts_with("[line 1, col 1] Error at 'return': Can't return from top-level code.")
+    );
+}
+
+#[test]
+fn ast_json_serializes_the_program() {
+    let output = jlox(&["--ast-json", "-"], "1 + 2;\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        stdout,
+        "[{\"type\":\"expression\",\"expression\":{\"type\":\"binary\",\"op\":\"+\",\"line\":1,\
+         \"left\":{\"type\":\"literal\",\"value\":1},\"right\":{\"type\":\"literal\",\"value\":2}}}]\n"
+    );
+}
+
+#[test]
+fn tokens_underlines_scanner_errors() {
+    let output = jlox(&["--tokens", "-"], "a @\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(65));
+    assert_eq!(
+        stderr,
+        "[line 1, col 3] Error: Unexpected character '@'.\na @\n  ^\n"
+    );
+}
+
+#[test]
+fn input_reads_the_next_line_of_stdin() {
+    let output = jlox(&[], "print input(\"? \");\nhello\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout, "> ? hello\n> ");
+}
+
+#[test]
+fn input_returns_nil_at_end_of_input() {
+    let output = jlox(&[], "print input(\"? \");\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!(stdout, "> ? nil\n> ");
+}
+
+#[test]
+fn repl_keeps_running_after_a_syntax_error() {
+    let output = jlox(&[], "var x = 1 +;\nprint 2;\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(stderr.starts_with("[line 1, col 12] Error at ';': Expect expression."));
+    assert_eq!(stdout, "> > 2\n> ");
+    assert_eq!(output.status.code(), Some(0));
+}
@@ -0,0 +1,82 @@
+//! Uso de la biblioteca desde otro programa.
+
+use jlox::{Lox, interpreter::Interpreter, resolver::Resolver};
+
+#[test]
+fn try_interpret_returns_the_runtime_error() {
+    let mut lox = Lox::new();
+    let statements = lox.parse("var a = 1;\nprint -\"x\";\na = 2;");
+    assert!(!lox.had_error);
+
+    let mut interpreter = Interpreter::new();
+    Resolver::new(&mut interpreter, &mut lox).resolve(&statements);
+    let error = interpreter.try_interpret(&statements).unwrap_err();
+
+    assert_eq!(error.message, "Operand must be a number.");
+    assert_eq!(error.token.line, 2);
+    assert!(!lox.had_runtime_error);
+}
+
+#[test]
+fn run_source_returns_diagnostics() {
+    assert_eq!(jlox::run_source("var a = 1;"), Ok(()));
+
+    let diagnostics = jlox::run_source("print 1 +;").unwrap_err();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Expect expression.");
+}
+
+#[test]
+fn run_source_reports_stack_overflow_on_a_small_stack() {
+    // El hilo de la prueba tiene la pila por defecto; `run_source` usa la suya.
+    let diagnostics = jlox::run_source("fun f(n) { return f(n + 1); } f(0);").unwrap_err();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "Stack overflow.");
+}
+
+const TWO_FAILURES: &str = "print -\"a\";\nprint -\"b\";";
+
+#[test]
+fn runtime_errors_continue_by_default() {
+    let mut lox = Lox::with_output(Box::new(std::io::sink()), Box::new(std::io::sink()));
+    lox.run(TWO_FAILURES);
+
+    assert!(lox.had_runtime_error);
+    assert_eq!(lox.diagnostics.len(), 2);
+}
+
+#[test]
+fn stop_on_error_runs_until_the_first_runtime_error() {
+    let mut lox = Lox::with_output(Box::new(std::io::sink()), Box::new(std::io::sink()));
+    lox.stop_on_error = true;
+    lox.run(TWO_FAILURES);
+
+    assert!(lox.had_runtime_error);
+    assert_eq!(lox.diagnostics.len(), 1);
+    assert_eq!(lox.diagnostics[0].line, 1);
+}
+
+#[test]
+fn code_after_return_is_a_warning() {
+    let mut lox = Lox::with_output(Box::new(std::io::sink()), Box::new(std::io::sink()));
+    lox.run("fun f() {\n  return 1;\n  print \"dead\";\n}\nf();");
+
+    assert!(!lox.had_error);
+    assert!(!lox.had_runtime_error);
+    assert_eq!(lox.diagnostics.len(), 1);
+    assert!(lox.diagnostics[0].warning);
+    assert_eq!(
+        lox.diagnostics[0].to_string(),
+        "[line 2, col 3] Warning at 'return': Unreachable code after 'return'."
+    );
+}
+
+#[test]
+fn division_by_zero_sets_had_runtime_error() {
+    let mut lox = Lox::with_output(Box::new(std::io::sink()), Box::new(std::io::sink()));
+    lox.run("print 1 / 0;");
+
+    assert!(!lox.had_error);
+    assert!(lox.had_runtime_error);
+    assert_eq!(lox.diagnostics[0].message, "Division by zero.");
+}
@@ -0,0 +1,33 @@
+//! Plegado de constantes, comprobado con el `ast_printer`.
+
+use jlox::{Lox, ast_printer, optimizer};
+
+/// Parsea y pliega un programa; devuelve cada sentencia parentizada.
+fn fold(source: &str) -> Vec<String> {
+    let mut lox = Lox::with_output(Box::new(std::io::sink()), Box::new(std::io::sink()));
+    let mut statements = lox.parse(source);
+    assert!(!lox.had_error, "{source:?} no compila");
+
+    optimizer::fold_constants(&mut statements);
+    statements.iter().map(ast_printer::print_stmt).collect()
+}
+
+#[test]
+fn folds_arithmetic_into_a_single_literal() {
+    assert_eq!(fold("print 1 + 2 * 3;"), ["(print 7)"]);
+    assert_eq!(fold("print -(4 - 6);"), ["(print 2)"]);
+}
+
+#[test]
+fn leaves_operations_that_can_fail_or_differ() {
+    assert_eq!(fold("print 1 / 0;"), ["(print (/ 1 0))"]);
+    assert_eq!(fold("print \"a\" + 1;"), ["(print (+ a 1))"]);
+}
+
+#[test]
+fn does_not_fold_variables() {
+    assert_eq!(
+        fold("var x = 1; print x + 2 * 3;"),
+        ["(var x 1)", "(print (+ x 6))"]
+    );
+}
@@ -0,0 +1,61 @@
+//! Precedencia y asociatividad del parser, comprobadas con el `ast_printer`.
+
+use jlox::{Lox, ast_printer};
+
+/// Parsea una sola sentencia de expresión y devuelve la expresión parentizada.
+fn parse(source: &str) -> String {
+    let mut lox = Lox::with_output(Box::new(std::io::sink()), Box::new(std::io::sink()));
+    let statements = lox.parse(&format!("{source};"));
+    assert!(
+        !lox.had_error,
+        "{source:?} no compila: {:?}",
+        lox.diagnostics
+    );
+    assert_eq!(statements.len(), 1);
+
+    let printed = ast_printer::print_stmt(&statements[0]);
+    printed
+        .strip_prefix("(; ")
+        .and_then(|expr| expr.strip_suffix(')'))
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn assignment_binds_looser_than_logical_operators() {
+    assert_eq!(parse("a = b or c"), "(= a (or b c))");
+    assert_eq!(parse("a = b and c"), "(= a (and b c))");
+    assert_eq!(parse("a = b = c or d"), "(= a (= b (or c d)))");
+}
+
+#[test]
+fn and_binds_tighter_than_or() {
+    assert_eq!(parse("a or b and c"), "(or a (and b c))");
+    assert_eq!(parse("a and b or c"), "(or (and a b) c)");
+    assert_eq!(parse("a or b or c"), "(or (or a b) c)");
+}
+
+#[test]
+fn equality_binds_tighter_than_and() {
+    assert_eq!(parse("a == b and c != d"), "(and (== a b) (!= c d))");
+    assert_eq!(parse("a == b < c or d"), "(or (== a (< b c)) d)");
+}
+
+#[test]
+fn conditional_sits_between_assignment_and_or() {
+    assert_eq!(parse("a = b ? c : d or e"), "(= a (?: b c (or d e)))");
+    assert_eq!(parse("a or b ? c : d"), "(?: (or a b) c d)");
+}
+
+#[test]
+fn arithmetic_precedence() {
+    assert_eq!(parse("a + b * c - d"), "(- (+ a (* b c)) d)");
+    assert_eq!(parse("a ** b ** c"), "(** a (** b c))");
+    assert_eq!(parse("!a == b"), "(== (! a) b)");
+}
+
+#[test]
+fn conditional_is_right_associative() {
+    assert_eq!(parse("a ? b : c ? d : e"), "(?: a b (?: c d e))");
+    assert_eq!(parse("a ? b ? c : d : e"), "(?: a (?: b c d) e)");
+}
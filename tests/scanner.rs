@@ -0,0 +1,250 @@
+//! Pruebas del scanner: posiciones de los tokens y errores léxicos.
+
+use std::{cell::RefCell, io, rc::Rc};
+
+use jlox::{
+    Lox,
+    scanner::Scanner,
+    token::{Literal, Token, TokenType},
+};
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn lines(source: &str) -> Vec<usize> {
+    let mut lox = Lox::with_output(Box::new(std::io::sink()), Box::new(std::io::sink()));
+    Scanner::new(source, &mut lox)
+        .scan_tokens()
+        .iter()
+        .map(|token| token.line)
+        .collect()
+}
+
+#[test]
+fn crlf_is_a_single_line_break() {
+    assert_eq!(lines("a\r\nb\r\n\r\nc"), [1, 2, 4, 4]);
+}
+
+#[test]
+fn lone_cr_is_a_line_break() {
+    assert_eq!(lines("a\rb // comentario\rc"), [1, 2, 3, 3]);
+}
+
+#[test]
+fn errors_after_cr_report_the_right_line() {
+    let mut lox = Lox::with_output(Box::new(std::io::sink()), Box::new(std::io::sink()));
+    lox.parse("var a = 1;\rvar b = 2;\r\nvar c = @;");
+
+    assert!(lox.had_error);
+    assert!(
+        lox.diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.line == 3)
+    );
+}
+
+#[test]
+fn underline_after_cr_shows_the_right_line() {
+    let err = SharedBuffer::default();
+    let mut lox = Lox::with_output(Box::new(io::sink()), Box::new(err.clone()));
+    lox.underline_errors = true;
+    lox.parse("var a = 1;\rvar b = @;\r\nprint b;");
+
+    let output = String::from_utf8(err.0.borrow().clone()).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines[..3],
+        [
+            "[line 2, col 9] Error: Unexpected character '@'.",
+            "var b = @;",
+            "        ^",
+        ]
+    );
+}
+
+/// Escanea `source` y devuelve los tokens junto con los mensajes de error.
+fn scan(source: &str) -> (Vec<Token>, Vec<String>) {
+    let mut lox = Lox::with_output(Box::new(io::sink()), Box::new(io::sink()));
+    let tokens = Scanner::new(source, &mut lox).scan_tokens();
+    let errors = lox
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect();
+    (tokens, errors)
+}
+
+fn kinds(source: &str) -> Vec<TokenType> {
+    let (tokens, errors) = scan(source);
+    assert!(errors.is_empty(), "{source:?}: {errors:?}");
+    tokens.iter().map(|token| token.kind).collect()
+}
+
+fn literal(source: &str) -> Option<Literal> {
+    let (tokens, errors) = scan(source);
+    assert!(errors.is_empty(), "{source:?}: {errors:?}");
+    assert_eq!(tokens.len(), 2, "{source:?} no es un solo token");
+    tokens[0].literal.clone()
+}
+
+#[test]
+fn block_comments_are_skipped() {
+    use TokenType::*;
+    assert_eq!(kinds("a /* comentario */ b"), [Identifier, Identifier, Eof]);
+    assert_eq!(lines("a /* una\ndos\n*/ b"), [1, 3, 3]);
+}
+
+#[test]
+fn block_comments_nest() {
+    use TokenType::*;
+    assert_eq!(
+        kinds("a /* outer /* inner */ still commented */ b"),
+        [Identifier, Identifier, Eof]
+    );
+}
+
+#[test]
+fn unterminated_block_comment_is_an_error() {
+    let (_, errors) = scan("a /* open /* nested */");
+    assert_eq!(errors, ["Unterminated block comment."]);
+}
+
+#[test]
+fn escapes_are_decoded() {
+    assert_eq!(literal(r#""\t""#), Some(Literal::Str("\t".to_string())));
+    assert_eq!(
+        literal(r#""a\n\r\\\"\0b""#),
+        Some(Literal::Str("a\n\r\\\"\0b".to_string()))
+    );
+}
+
+#[test]
+fn unknown_escape_is_an_error() {
+    let (_, errors) = scan(r#""a\qb""#);
+    assert_eq!(errors, [r"Invalid escape sequence '\q'."]);
+}
+
+#[test]
+fn hex_literals() {
+    assert_eq!(literal("0xFF"), Some(Literal::Number(255.0)));
+    assert_eq!(literal("0Xa0"), Some(Literal::Number(160.0)));
+
+    let mut lox = Lox::with_output(Box::new(io::sink()), Box::new(io::sink()));
+    lox.parse("print 0x;");
+    let errors: Vec<String> = lox
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    assert_eq!(errors, ["[line 1, col 9] Error: Invalid hex literal."]);
+}
+
+#[test]
+fn digit_separators() {
+    use TokenType::*;
+    assert_eq!(literal("1_000.5"), Some(Literal::Number(1000.5)));
+    assert_eq!(kinds("_x"), [Identifier, Eof]);
+    // `1_` termina en `1`; el `_` se escanea aparte.
+    assert_eq!(kinds("1_"), [Number, Identifier, Eof]);
+
+    let (_, errors) = scan("1__0");
+    assert_eq!(errors, ["Invalid digit separator '__' in number."]);
+}
+
+#[test]
+fn scientific_notation() {
+    use TokenType::*;
+    assert_eq!(literal("1e10"), Some(Literal::Number(1e10)));
+    assert_eq!(literal("2.5e-3"), Some(Literal::Number(2.5e-3)));
+    // Sin dígitos tras la `e` no hay exponente: `1` y luego el identificador `e`.
+    assert_eq!(kinds("1e"), [Number, Identifier, Eof]);
+}
+
+#[test]
+fn unexpected_character_is_named() {
+    let (_, errors) = scan("a @ b");
+    assert_eq!(errors, ["Unexpected character '@'."]);
+
+    let (_, errors) = scan("€");
+    assert_eq!(errors, ["Unexpected character '€'."]);
+}
+
+#[test]
+fn token_spans_are_character_offsets() {
+    let (tokens, errors) = scan("\"ñ\" + ab");
+    assert!(errors.is_empty());
+    let spans: Vec<(usize, usize)> = tokens
+        .iter()
+        .map(|token| (token.start, token.len))
+        .collect();
+    assert_eq!(spans, [(0, 3), (4, 1), (6, 2), (8, 0)]);
+}
+
+#[test]
+fn equal_tokens_compare_equal() {
+    let number = || Token::new(TokenType::Number, "1", Some(Literal::Number(1.0)), 1, 5);
+    assert_eq!(number(), number());
+}
+
+#[test]
+fn tokens_on_different_lines_differ() {
+    let a = Token::new(TokenType::Identifier, "a", None, 1, 1);
+    let b = Token::new(TokenType::Identifier, "a", None, 2, 1);
+    assert_ne!(a, b);
+}
+
+#[test]
+fn literals_compare_numbers_by_bit_pattern() {
+    // Con `to_bits`, NaN es igual a sí mismo y la igualdad es reflexiva (`Eq`).
+    assert_eq!(Literal::Number(f64::NAN), Literal::Number(f64::NAN));
+    assert_ne!(Literal::Number(0.0), Literal::Number(-0.0));
+}
+
+#[test]
+fn multi_line_string_is_a_single_token() {
+    let (tokens, errors) = scan("\"ab\ncd\" x");
+    assert!(errors.is_empty());
+
+    let kinds: Vec<TokenType> = tokens.iter().map(|token| token.kind).collect();
+    assert_eq!(
+        kinds,
+        [TokenType::String, TokenType::Identifier, TokenType::Eof]
+    );
+    assert_eq!(tokens[0].literal, Some(Literal::Str("ab\ncd".to_string())));
+    assert_eq!(tokens[1].line, 2);
+}
+
+#[test]
+fn large_multibyte_source() {
+    const LINES: usize = 20_000;
+    let source: String = (0..LINES)
+        .map(|i| format!("var v{i} = \"ñandú {i} 🦀\"; // café\n"))
+        .collect();
+
+    let (tokens, errors) = scan(&source);
+    assert!(errors.is_empty());
+    assert_eq!(tokens.len(), LINES * 5 + 1);
+
+    let last = &tokens[(LINES - 1) * 5..];
+    let lexemes: Vec<&str> = last.iter().map(|token| token.lexeme.as_str()).collect();
+    assert_eq!(
+        lexemes,
+        ["var", "v19999", "=", "\"ñandú 19999 🦀\"", ";", ""]
+    );
+    assert_eq!(last[0].line, LINES);
+    assert_eq!(
+        last[3].literal,
+        Some(Literal::Str("ñandú 19999 🦀".to_string()))
+    );
+}
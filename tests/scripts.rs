@@ -0,0 +1,125 @@
+//! Ejecuta cada script de `tests/scripts` y compara su salida con las anotaciones:
+//!
+//! - `// expect: <línea>`: línea esperada en la salida estándar, en orden.
+//! - `// expect runtime error: <mensaje>`: el programa falla en ejecución con ese mensaje.
+//! - `// expect error: <mensaje>`: el programa no compila y se informa ese mensaje.
+
+use std::{
+    cell::RefCell,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+    thread,
+};
+
+use jlox::Lox;
+
+#[derive(Clone, Default)]
+struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Expectations {
+    output: Vec<String>,
+    runtime_errors: Vec<String>,
+    errors: Vec<String>,
+}
+
+fn expectations(source: &str) -> Expectations {
+    let mut expected = Expectations::default();
+    for line in source.lines() {
+        if let Some((_, text)) = line.split_once("// expect runtime error: ") {
+            expected.runtime_errors.push(text.to_string());
+        } else if let Some((_, text)) = line.split_once("// expect error: ") {
+            expected.errors.push(text.to_string());
+        } else if let Some((_, text)) = line.split_once("// expect: ") {
+            expected.output.push(text.to_string());
+        }
+    }
+    expected
+}
+
+/// Devuelve la descripción del fallo, o `None` si el script se comporta como se espera.
+fn check(path: &Path) -> Option<String> {
+    let source = fs::read_to_string(path).unwrap();
+    let expected = expectations(&source);
+
+    let out = SharedBuffer::default();
+    let err = SharedBuffer::default();
+    let mut lox = Lox::with_output(Box::new(out.clone()), Box::new(err.clone()));
+    lox.set_script_dir(path.parent().unwrap());
+    lox.run(&source);
+
+    let output = out.contents();
+    let output: Vec<&str> = output.lines().collect();
+    let errors = err.contents();
+
+    let mut problems = Vec::new();
+    if output != expected.output {
+        problems.push(format!(
+            "expected output {:?}\n  got {:?}",
+            expected.output, output
+        ));
+    }
+    if lox.had_error == expected.errors.is_empty() {
+        problems.push(format!("unexpected compile errors:\n{errors}"));
+    }
+    if lox.had_runtime_error == expected.runtime_errors.is_empty() {
+        problems.push(format!("unexpected runtime errors:\n{errors}"));
+    }
+    for message in expected.errors.iter().chain(&expected.runtime_errors) {
+        if !errors.contains(message.as_str()) {
+            problems.push(format!("missing error {message:?} in:\n{errors}"));
+        }
+    }
+
+    if problems.is_empty() {
+        None
+    } else {
+        Some(format!("{}:\n  {}", path.display(), problems.join("\n  ")))
+    }
+}
+
+#[test]
+fn scripts() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/scripts");
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "lox"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty(), "no scripts found");
+
+    // Igual que el binario, se usa una pila amplia para las pruebas de recursión.
+    let failures = thread::Builder::new()
+        .stack_size(jlox::STACK_SIZE)
+        .spawn(move || {
+            paths
+                .iter()
+                .filter_map(|path| check(path))
+                .collect::<Vec<_>>()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n\n"));
+}